@@ -0,0 +1,181 @@
+// benches/wide_site.rs
+//
+// Crawls synthetic wide sites served from localhost and reports pages per
+// second. Run with `cargo bench --bench wide_site`.
+//
+// Two scenarios:
+// - one wide site with no politeness delay, which is CPU-bound here;
+// - several sites crawled together with a delay between requests to the
+//   same host. Workers must keep fetching from the other sites while one
+//   cools down, so this is where the pool's scheduling shows.
+use std::time::{Duration, Instant};
+
+use rust_crawler::{CrawlerBuilder, Seed};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+// Links per page; with depth 3 the crawl fetches 1 + 60 + 3600 pages
+const FANOUT: usize = 60;
+const MAX_DEPTH: u32 = 3;
+// Simulated server think time per response
+const LATENCY: Duration = Duration::from_millis(5);
+const RUNS: usize = 3;
+
+// The multi-site scenario: sites with 1 + 30 pages each, crawled with 20ms
+// between requests to the same site
+const SITES: usize = 8;
+const SITE_FANOUT: usize = 30;
+const HOST_DELAY: Duration = Duration::from_millis(20);
+const SITES_CONCURRENCY: usize = 32;
+
+async fn serve(listener: TcpListener, fanout: usize) {
+    loop {
+        let Ok((mut socket, _)) = listener.accept().await else {
+            continue;
+        };
+
+        tokio::spawn(async move {
+            let mut buf = vec![0; 4096];
+            loop {
+                let n = match socket.read(&mut buf).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => n,
+                };
+
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or("/")
+                    .trim_end_matches('/')
+                    .to_string();
+
+                tokio::time::sleep(LATENCY).await;
+
+                let body: String = (0..fanout)
+                    .map(|i| format!("<a href=\"{}/{}\">{}</a>", path, i, i))
+                    .collect();
+                let body = format!(
+                    "<html><head><title>{}</title></head><body>{}</body></html>",
+                    path, body
+                );
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+
+                if socket.write_all(response.as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+        });
+    }
+}
+
+// Serve a site on a port of its own, so it is its own politeness host
+async fn start_site(fanout: usize) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}/", listener.local_addr().unwrap());
+    tokio::spawn(serve(listener, fanout));
+    base
+}
+
+async fn crawl(
+    seeds: &[String],
+    max_depth: u32,
+    concurrent_tasks: usize,
+    delay: Duration,
+) -> (usize, Duration) {
+    let crawler = CrawlerBuilder::new()
+        .max_depth(max_depth)
+        .concurrent_tasks(concurrent_tasks)
+        .delay_between_requests(delay)
+        .crawl_timeout(Duration::from_secs(300))
+        .respect_robots_txt(false)
+        .build()
+        .expect("crawler builds");
+
+    let started = Instant::now();
+    let result = crawler
+        .crawl_seeds(seeds.iter().map(Seed::new).collect())
+        .await
+        .expect("crawl succeeds");
+    (result.pages.len(), started.elapsed())
+}
+
+fn median(mut times: Vec<Duration>) -> Duration {
+    times.sort();
+    times[times.len() / 2]
+}
+
+async fn wide_site() {
+    let seeds = vec![start_site(FANOUT).await];
+
+    println!(
+        "wide site: fanout {}, depth {}, {}ms latency",
+        FANOUT,
+        MAX_DEPTH,
+        LATENCY.as_millis()
+    );
+
+    for concurrent_tasks in [8, 32, 128, 256, 512] {
+        let mut times = Vec::with_capacity(RUNS);
+        let mut pages = 0;
+        for _ in 0..RUNS {
+            let (crawled, elapsed) =
+                crawl(&seeds, MAX_DEPTH, concurrent_tasks, Duration::ZERO).await;
+            pages = crawled;
+            times.push(elapsed);
+        }
+        let median = median(times);
+
+        println!(
+            "concurrent_tasks {:>4}: {} pages in {:>8.1}ms, {:>7.0} pages/s",
+            concurrent_tasks,
+            pages,
+            median.as_secs_f64() * 1000.0,
+            pages as f64 / median.as_secs_f64()
+        );
+    }
+}
+
+async fn many_sites() {
+    let mut seeds = Vec::with_capacity(SITES);
+    for _ in 0..SITES {
+        seeds.push(start_site(SITE_FANOUT).await);
+    }
+
+    println!(
+        "\n{} sites: fanout {}, depth 2, {}ms between requests to a site, {} workers",
+        SITES,
+        SITE_FANOUT,
+        HOST_DELAY.as_millis(),
+        SITES_CONCURRENCY
+    );
+
+    let mut times = Vec::with_capacity(RUNS);
+    let mut pages = 0;
+    for _ in 0..RUNS {
+        let (crawled, elapsed) = crawl(&seeds, 2, SITES_CONCURRENCY, HOST_DELAY).await;
+        pages = crawled;
+        times.push(elapsed);
+    }
+    let median = median(times);
+
+    // Sites are crawled side by side, so each one's delays set the floor
+    let floor = HOST_DELAY * SITE_FANOUT as u32;
+    println!(
+        "{} pages in {:.1}ms ({:.1}ms if every site is busy throughout), {:.0} pages/s",
+        pages,
+        median.as_secs_f64() * 1000.0,
+        floor.as_secs_f64() * 1000.0,
+        pages as f64 / median.as_secs_f64()
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    wide_site().await;
+    many_sites().await;
+}
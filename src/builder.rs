@@ -0,0 +1,270 @@
+// src/builder.rs
+use reqwest::Client;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::CrawlerConfig;
+use crate::crawler::Crawler;
+use crate::error::{CrawlerError, Result};
+use crate::frontier::{Frontier, FrontierStrategy, ScoreFn, SpillingFrontier};
+use crate::incremental::PreviousCrawl;
+use crate::page::Page;
+use crate::parser::ParsePool;
+use crate::retry::RetryPolicy;
+use crate::robots::RobotsChecker;
+use crate::seed::Seed;
+use crate::sink::OutputSink;
+use crate::visited::VisitedStore;
+
+/// Builds a [`Crawler`] from a [`CrawlerConfig`] plus optional overrides.
+///
+/// When no client is supplied, one is built from the config's user agent,
+/// request timeout and redirect settings. When no robots checker is supplied,
+/// one is created on top of the crawler's client.
+#[derive(Default)]
+pub struct CrawlerBuilder {
+    config: CrawlerConfig,
+    client: Option<Client>,
+    robots_checker: Option<RobotsChecker>,
+    frontier: Option<Box<dyn Frontier>>,
+    visited: Option<Box<dyn VisitedStore>>,
+    score: Option<ScoreFn>,
+    sinks: Vec<Arc<dyn OutputSink>>,
+    previous: Option<PreviousCrawl>,
+}
+
+impl CrawlerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_config(config: CrawlerConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn config(mut self, config: CrawlerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: u32) -> Self {
+        self.config.max_depth = max_depth;
+        self
+    }
+
+    pub fn concurrent_tasks(mut self, concurrent_tasks: usize) -> Self {
+        self.config.concurrent_tasks = concurrent_tasks;
+        self
+    }
+
+    pub fn parse_threads(mut self, parse_threads: usize) -> Self {
+        self.config.parse_threads = Some(parse_threads);
+        self
+    }
+
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.config.request_timeout = timeout;
+        self
+    }
+
+    pub fn crawl_timeout(mut self, timeout: Duration) -> Self {
+        self.config.crawl_timeout = timeout;
+        self
+    }
+
+    pub fn delay_between_requests(mut self, delay: Duration) -> Self {
+        self.config.delay_between_requests = delay;
+        self
+    }
+
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.config.user_agent = user_agent.into();
+        self
+    }
+
+    /// Add a start URL used by [`Crawler::crawl_seeds`] alongside any passed in.
+    pub fn seed(mut self, seed: Seed) -> Self {
+        self.config.seeds.push(seed);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.config.retry = retry;
+        self
+    }
+
+    pub fn respect_robots_txt(mut self, respect: bool) -> Self {
+        self.config.respect_robots_txt = respect;
+        self
+    }
+
+    pub fn allowed_domains(mut self, domains: Vec<String>) -> Self {
+        self.config.allowed_domains = domains;
+        self
+    }
+
+    pub fn excluded_paths(mut self, paths: Vec<String>) -> Self {
+        self.config.excluded_paths = paths;
+        self
+    }
+
+    pub fn max_urls_per_domain(mut self, max: usize) -> Self {
+        self.config.max_urls_per_domain = Some(max);
+        self
+    }
+
+    pub fn max_total_urls(mut self, max: usize) -> Self {
+        self.config.max_total_urls = Some(max);
+        self
+    }
+
+    /// Periodically snapshot the crawl state to `path` so it can be resumed
+    /// with [`Crawler::resume`].
+    pub fn checkpoint<P: Into<String>>(mut self, path: P, interval: Duration) -> Self {
+        self.config.checkpoint_path = Some(path.into());
+        self.config.checkpoint_interval = interval;
+        self
+    }
+
+    pub fn frontier_strategy(mut self, strategy: FrontierStrategy) -> Self {
+        self.config.frontier_strategy = strategy;
+        self
+    }
+
+    /// Score function for the best-first frontier; higher scores are crawled
+    /// first. Has no effect with other strategies.
+    pub fn score_fn<F>(mut self, score: F) -> Self
+    where
+        F: Fn(&Page) -> f64 + Send + Sync + 'static,
+    {
+        self.score = Some(Arc::new(score));
+        self
+    }
+
+    /// Use a caller-provided frontier instead of the configured strategy.
+    pub fn frontier<F: Frontier + 'static>(mut self, frontier: F) -> Self {
+        self.frontier = Some(Box::new(frontier));
+        self
+    }
+
+    /// Use a custom visited-URL store instead of the one selected by the
+    /// config.
+    pub fn visited_store<V: VisitedStore + 'static>(mut self, visited: V) -> Self {
+        self.visited = Some(Box::new(visited));
+        self
+    }
+
+    /// Use a caller-provided HTTP client. The config's user agent and
+    /// timeout are not applied to it. Build it with
+    /// `redirect::Policy::none()` so the crawler sees, records and follows
+    /// redirects itself.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Use a caller-provided robots.txt checker, e.g. to share its cache
+    /// between several crawlers.
+    pub fn robots_checker(mut self, robots_checker: RobotsChecker) -> Self {
+        self.robots_checker = Some(robots_checker);
+        self
+    }
+
+    /// Revalidate pages against the results file of an earlier crawl. The
+    /// file is read when the crawler is built.
+    pub fn previous_results<S: Into<String>>(mut self, path: S) -> Self {
+        self.config.previous_results = Some(path.into());
+        self
+    }
+
+    /// Revalidate pages against an already loaded earlier crawl; takes
+    /// precedence over `previous_results`.
+    pub fn previous_crawl(mut self, previous: PreviousCrawl) -> Self {
+        self.previous = Some(previous);
+        self
+    }
+
+    /// Register a sink that receives the result when a crawl finishes.
+    pub fn sink<S: OutputSink + 'static>(mut self, sink: S) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    pub fn build(self) -> Result<Crawler> {
+        let client = match self.client {
+            Some(client) => client,
+            None => build_client(&self.config, reqwest::redirect::Policy::none())?,
+        };
+
+        // Sitemaps are fetched with the robots checker's client, which
+        // follows redirects
+        let robots_checker = match self.robots_checker {
+            Some(robots_checker) => robots_checker,
+            None => RobotsChecker::from_config(&self.config)?,
+        };
+
+        let mut frontier = match self.frontier {
+            Some(frontier) => frontier,
+            None => self.config.frontier_strategy.build(self.score.clone()),
+        };
+
+        if let Some(limit) = self.config.frontier_memory_limit {
+            let score_band = self.config.frontier_score_band;
+            if !(score_band > 0.0 && score_band.is_finite()) {
+                return Err(CrawlerError::ConfigError(format!(
+                    "frontier_score_band must be a positive number, got {}",
+                    score_band
+                )));
+            }
+            frontier = Box::new(SpillingFrontier::new(
+                frontier,
+                limit,
+                self.config.frontier_spill_dir.as_ref().map(PathBuf::from),
+                self.config
+                    .frontier_strategy
+                    .spill_order(self.score, score_band),
+            )?);
+        }
+
+        let visited = match self.visited {
+            Some(visited) => visited,
+            None => self.config.visited_store.build()?,
+        };
+
+        let previous = match (self.previous, &self.config.previous_results) {
+            (Some(previous), _) => Some(previous),
+            (None, Some(path)) => Some(PreviousCrawl::load(path)?),
+            (None, None) => None,
+        };
+
+        let parser = ParsePool::new(self.config.parse_threads)?;
+
+        Ok(Crawler::from_parts(
+            self.config,
+            client,
+            robots_checker,
+            frontier,
+            visited,
+            parser,
+            self.sinks,
+            previous,
+        ))
+    }
+}
+
+pub(crate) fn build_client(
+    config: &CrawlerConfig,
+    redirect: reqwest::redirect::Policy,
+) -> Result<Client> {
+    // Create HTTP client with proper settings
+    Client::builder()
+        .user_agent(&config.user_agent)
+        .timeout(config.request_timeout)
+        .redirect(redirect)
+        .build()
+        .map_err(CrawlerError::RequestError)
+}
@@ -0,0 +1,161 @@
+// src/canonical.rs
+use std::collections::{HashMap, HashSet};
+
+use crate::page::Page;
+
+/// Groups crawled pages by the canonical URL they declare. Each entry maps a
+/// canonical URL to the URLs of the pages pointing at it, sorted; pages
+/// without a canonical are left out.
+pub fn canonical_groups(pages: &[Page]) -> HashMap<String, Vec<String>> {
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+
+    for page in pages {
+        if let Some(canonical) = &page.canonical {
+            groups
+                .entry(canonical.clone())
+                .or_default()
+                .push(page.url.clone());
+        }
+    }
+
+    for urls in groups.values_mut() {
+        urls.sort();
+        urls.dedup();
+    }
+
+    groups
+}
+
+/// Rewrites a link graph so every URL variant of a page is replaced by the
+/// page's canonical URL. Variants' outgoing links are merged under the
+/// canonical node, and duplicate edges are dropped.
+///
+/// `content_url` gives the graph node holding a page's own links, which for
+/// a redirected page is its (normalized) final URL.
+pub fn collapse_graph(
+    graph: &HashMap<String, Vec<String>>,
+    pages: &[Page],
+    content_url: impl Fn(&Page) -> String,
+) -> HashMap<String, Vec<String>> {
+    // Both the requested URL and where its redirects landed are variants
+    let mut aliases = HashMap::new();
+    for page in pages {
+        let Some(canonical) = &page.canonical else {
+            continue;
+        };
+
+        for url in [page.url.clone(), content_url(page)] {
+            if url != *canonical {
+                aliases.insert(url, canonical.as_str());
+            }
+        }
+    }
+
+    let resolve = |url: &str| aliases.get(url).copied().unwrap_or(url).to_string();
+
+    let mut collapsed: HashMap<String, Vec<String>> = HashMap::new();
+    let mut seen_edges = HashSet::new();
+
+    for (source, targets) in graph {
+        let source = resolve(source);
+        let links = collapsed.entry(source.clone()).or_default();
+
+        for target in targets {
+            let target = resolve(target);
+            if seen_edges.insert((source.clone(), target.clone())) {
+                links.push(target);
+            }
+        }
+    }
+
+    collapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        edges
+            .iter()
+            .map(|(source, targets)| {
+                let targets = targets.iter().map(|t| t.to_string()).collect();
+                (source.to_string(), targets)
+            })
+            .collect()
+    }
+
+    fn page(url: &str, final_url: Option<&str>, canonical: Option<&str>) -> Page {
+        let mut page = Page::new(url.to_string(), 0);
+        page.final_url = final_url.map(str::to_string);
+        page.canonical = canonical.map(str::to_string);
+        page
+    }
+
+    // Final URLs as the crawler keys graph nodes: without a trailing slash
+    fn content_url(page: &Page) -> String {
+        match &page.final_url {
+            Some(final_url) => final_url.trim_end_matches('/').to_string(),
+            None => page.url.clone(),
+        }
+    }
+
+    #[test]
+    fn groups_pages_by_canonical() {
+        let pages = [
+            page("https://a.com/b?x=1", None, Some("https://a.com/b")),
+            page("https://a.com/b", None, Some("https://a.com/b")),
+            page("https://a.com/c", None, None),
+        ];
+
+        let groups = canonical_groups(&pages);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups["https://a.com/b"],
+            vec!["https://a.com/b", "https://a.com/b?x=1"]
+        );
+    }
+
+    #[test]
+    fn merges_variants_into_canonical() {
+        let pages = [
+            page("https://a.com/b?x=1", None, Some("https://a.com/b")),
+            page("https://a.com/b", None, Some("https://a.com/b")),
+        ];
+        let graph = graph(&[
+            (
+                "https://a.com/b?x=1",
+                &["https://a.com/c", "https://a.com/b"],
+            ),
+            ("https://a.com/b", &["https://a.com/c"]),
+            ("https://a.com/c", &["https://a.com/b?x=1"]),
+        ]);
+
+        let collapsed = collapse_graph(&graph, &pages, content_url);
+        assert_eq!(collapsed.len(), 2);
+        let mut links = collapsed["https://a.com/b"].clone();
+        links.sort();
+        assert_eq!(links, vec!["https://a.com/b", "https://a.com/c"]);
+        assert_eq!(collapsed["https://a.com/c"], vec!["https://a.com/b"]);
+    }
+
+    #[test]
+    fn merges_redirected_variant_by_its_graph_node() {
+        // `/old` redirected to `/docs/`, whose node is the normalized `/docs`
+        let pages = [page(
+            "https://a.com/old",
+            Some("https://a.com/docs/"),
+            Some("https://a.com/guide"),
+        )];
+        let graph = graph(&[
+            ("https://a.com/old", &["https://a.com/docs"]),
+            ("https://a.com/docs", &["https://a.com/x"]),
+        ]);
+
+        let collapsed = collapse_graph(&graph, &pages, content_url);
+        assert_eq!(collapsed.len(), 1);
+        let mut links = collapsed["https://a.com/guide"].clone();
+        links.sort();
+        assert_eq!(links, vec!["https://a.com/guide", "https://a.com/x"]);
+    }
+}
@@ -0,0 +1,58 @@
+// src/checkpoint.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::crawler::CrawlStats;
+use crate::error::{CrawlerError, Result};
+use crate::page::Page;
+use crate::seed::Seed;
+
+/// A snapshot of everything needed to continue an interrupted crawl.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub created_at: DateTime<Utc>,
+    pub visited: Vec<String>,
+    /// Pages that were queued or in flight when the snapshot was taken
+    pub pending: Vec<Page>,
+    pub graph: HashMap<String, Vec<String>>,
+    pub pages: Vec<Page>,
+    pub domain_counters: HashMap<String, usize>,
+    pub stats: CrawlStats,
+    #[serde(default)]
+    pub seeds: Vec<Seed>,
+    /// URLs left unfetched because robots.txt disallows them
+    #[serde(default)]
+    pub robots_disallowed: Vec<String>,
+}
+
+/// Writes the checkpoint as JSON. The file is written next to `path` first and
+/// then renamed over it, so a crash mid-write never leaves a truncated file.
+pub fn save_checkpoint<P: AsRef<Path>>(checkpoint: &Checkpoint, path: P) -> Result<()> {
+    let path = path.as_ref();
+    let tmp_path = path.with_extension("tmp");
+
+    let file = File::create(&tmp_path).map_err(|e| {
+        CrawlerError::StorageError(format!("Failed to create checkpoint file: {}", e))
+    })?;
+
+    serde_json::to_writer(BufWriter::new(file), checkpoint)
+        .map_err(|e| CrawlerError::StorageError(format!("Failed to write checkpoint: {}", e)))?;
+
+    fs::rename(&tmp_path, path).map_err(|e| {
+        CrawlerError::StorageError(format!("Failed to replace checkpoint file: {}", e))
+    })?;
+
+    Ok(())
+}
+
+pub fn load_checkpoint<P: AsRef<Path>>(path: P) -> Result<Checkpoint> {
+    let file = File::open(path.as_ref())
+        .map_err(|e| CrawlerError::StorageError(format!("Failed to open checkpoint: {}", e)))?;
+
+    serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| CrawlerError::StorageError(format!("Failed to parse checkpoint: {}", e)))
+}
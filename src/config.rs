@@ -1,28 +1,94 @@
 // src/config.rs
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 use std::time::Duration;
 
+use crate::directives::DirectivePolicies;
 use crate::error::{CrawlerError, Result};
+use crate::frontier::FrontierStrategy;
+use crate::normalize::NormalizationRules;
+use crate::retry::RetryPolicy;
+use crate::robots::RobotsConfig;
+use crate::seed::Seed;
+use crate::visited::VisitedStoreConfig;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CrawlerConfig {
     pub max_depth: u32,
+    /// Number of workers fetching pages at the same time
     pub concurrent_tasks: usize,
+    /// Threads parsing HTML, apart from the fetch workers (one per CPU when
+    /// unset)
+    pub parse_threads: Option<usize>,
     #[serde(with = "duration_serde")]
     pub request_timeout: Duration,
     #[serde(with = "duration_serde")]
     pub crawl_timeout: Duration,
     #[serde(with = "duration_serde")]
     pub delay_between_requests: Duration,
+    /// Per-host delays (keyed by host, or `host:port`), used where they are
+    /// longer than the delay that would otherwise apply
+    #[serde(with = "duration_map_serde")]
+    pub host_delays: BTreeMap<String, Duration>,
+    /// How long in-flight fetches may run on after a shutdown or timeout
+    #[serde(with = "duration_serde")]
+    pub shutdown_grace_period: Duration,
     pub user_agent: String,
     pub respect_robots_txt: bool,
+    /// How robots.txt files are fetched and cached
+    pub robots: RobotsConfig,
+    /// Whether meta robots, `X-Robots-Tag` and `rel="nofollow"` directives
+    /// are obeyed, only recorded, or ignored
+    pub robots_directives: DirectivePolicies,
     pub follow_redirects: bool,
+    /// Redirect hops followed per page before giving up
+    pub max_redirects: usize,
+    pub retry: RetryPolicy,
     pub allowed_domains: Vec<String>,
     pub excluded_paths: Vec<String>,
+    /// How URL variants are folded together before deduplication
+    pub normalization: NormalizationRules,
+    /// Where the set of already-seen URLs is kept
+    pub visited_store: VisitedStoreConfig,
     pub max_urls_per_domain: Option<usize>,
     pub max_total_urls: Option<usize>,
+    /// Order in which queued pages are crawled
+    pub frontier_strategy: FrontierStrategy,
+    /// Most queued pages kept in memory; the rest are spilled to disk.
+    /// Unbounded when unset.
+    pub frontier_memory_limit: Option<usize>,
+    /// Width of the score bands best-first pages are spilled in. Spilled
+    /// pages come back best band first, but within a band may be crawled
+    /// after slightly worse pages that stayed in memory.
+    pub frontier_score_band: f64,
+    /// Where spilled pages go, each frontier in a fresh subdirectory (the
+    /// system temp dir when unset)
+    pub frontier_spill_dir: Option<String>,
+    /// Where to periodically write crawl checkpoints (disabled when unset)
+    pub checkpoint_path: Option<String>,
+    #[serde(with = "duration_serde")]
+    pub checkpoint_interval: Duration,
+    /// Seed the frontier from each seed site's sitemaps (robots.txt
+    /// `Sitemap:` lines, or `/sitemap.xml`)
+    pub use_sitemaps: bool,
+    /// Cap on the number of URLs taken from one site's sitemaps
+    pub max_sitemap_urls: Option<usize>,
+    /// Merge pages declaring the same `rel=canonical` URL into one node of
+    /// the link graph
+    pub collapse_canonical_duplicates: bool,
+    /// Largest SimHash Hamming distance (out of 64 bits) at which two pages
+    /// count as near-duplicates
+    pub near_duplicate_distance: u32,
+    /// Don't follow links out of pages that duplicate an earlier page
+    pub skip_duplicate_links: bool,
+    /// Results file of an earlier crawl; its pages are revalidated with
+    /// conditional requests instead of being downloaded again
+    pub previous_results: Option<String>,
+    /// Start URLs crawled in addition to any given on the command line
+    pub seeds: Vec<Seed>,
 }
 
 impl Default for CrawlerConfig {
@@ -30,22 +96,44 @@ impl Default for CrawlerConfig {
         Self {
             max_depth: 2,
             concurrent_tasks: 8,
+            parse_threads: None,
             request_timeout: Duration::from_secs(10),
             crawl_timeout: Duration::from_secs(120),
             delay_between_requests: Duration::from_millis(100),
+            host_delays: BTreeMap::new(),
+            shutdown_grace_period: Duration::from_secs(5),
             user_agent: "RustCrawler/1.0 (https://example.com/bot)".to_string(),
             respect_robots_txt: true,
+            robots: RobotsConfig::default(),
+            robots_directives: DirectivePolicies::default(),
             follow_redirects: true,
+            max_redirects: 10,
+            retry: RetryPolicy::default(),
             allowed_domains: Vec::new(),
             excluded_paths: Vec::new(),
+            normalization: NormalizationRules::default(),
+            visited_store: VisitedStoreConfig::default(),
             max_urls_per_domain: None,
             max_total_urls: None,
+            frontier_strategy: FrontierStrategy::default(),
+            frontier_memory_limit: None,
+            frontier_spill_dir: None,
+            frontier_score_band: 0.1,
+            checkpoint_path: None,
+            checkpoint_interval: Duration::from_secs(60),
+            use_sitemaps: false,
+            max_sitemap_urls: None,
+            collapse_canonical_duplicates: false,
+            near_duplicate_distance: 3,
+            skip_duplicate_links: false,
+            previous_results: None,
+            seeds: Vec::new(),
         }
     }
 }
 
 // Helper module for serializing Duration
-mod duration_serde {
+pub(crate) mod duration_serde {
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
     use std::time::Duration;
 
@@ -66,6 +154,36 @@ mod duration_serde {
     }
 }
 
+// Helper module for serializing a map of Durations, in milliseconds like
+// `duration_serde`
+pub(crate) mod duration_map_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    pub fn serialize<S>(map: &BTreeMap<String, Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let millis: BTreeMap<&String, u128> = map
+            .iter()
+            .map(|(key, duration)| (key, duration.as_millis()))
+            .collect();
+        millis.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<BTreeMap<String, Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let millis = BTreeMap::<String, u64>::deserialize(deserializer)?;
+        Ok(millis
+            .into_iter()
+            .map(|(key, millis)| (key, Duration::from_millis(millis)))
+            .collect())
+    }
+}
+
 pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<CrawlerConfig> {
     // Fix: Use path.as_ref() to avoid moving the path
     let content = fs::read_to_string(path.as_ref())
@@ -121,4 +239,3 @@ pub fn create_example_config<P: AsRef<Path>>(path: P) -> Result<()> {
 
     Ok(())
 }
-
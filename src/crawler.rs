@@ -1,20 +1,44 @@
 // src/crawler.rs
 use chrono::Utc;
+use dashmap::{DashMap, DashSet};
 use futures::future::join_all;
 use log::{debug, error, info, warn};
-use reqwest::Client;
-use scraper::{Html, Selector};
+use reqwest::header::{HeaderMap, ETAG, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{mpsc, Mutex, Semaphore};
+use tokio::sync::{mpsc, watch, Mutex, Notify, RwLock};
 use url::Url;
 
+use crate::builder::CrawlerBuilder;
+use crate::canonical;
+use crate::checkpoint::{self, Checkpoint};
 use crate::config::CrawlerConfig;
+use crate::directives;
 use crate::error::{CrawlerError, Result};
-use crate::page::Page;
+use crate::events::{CrawlEvent, CrawlEventStream, SkipReason};
+use crate::fingerprint::{self, DuplicateCluster, DuplicateIndex};
+use crate::frontier::Frontier;
+use crate::incremental::PreviousCrawl;
+use crate::page::{Page, RedirectHop};
+use crate::parser::ParsePool;
+use crate::politeness::{self, HostScheduler, ParkedPages};
+use crate::retry;
 use crate::robots::RobotsChecker;
+use crate::seed::Seed;
+use crate::sink::OutputSink;
+use crate::sitemap::SitemapFetcher;
+use crate::storage::StoredPage;
+use crate::visited::VisitedStore;
+
+// How many events a streaming crawl may run ahead of its consumer
+const EVENT_BUFFER: usize = 256;
+// How many pages may wait for their host to be due before workers stop
+// taking new pages from the frontier
+const MAX_PARKED_PAGES: usize = 1024;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrawlStats {
@@ -24,6 +48,32 @@ pub struct CrawlStats {
     pub success_count: usize,
     pub error_count: usize,
     pub avg_page_size: usize,
+    /// Pages the server reported unchanged since the previous crawl
+    #[serde(default)]
+    pub not_modified_count: usize,
+    /// HTML pages parsed, and the time spent parsing them on the parser
+    /// pool
+    #[serde(default)]
+    pub parsed_count: usize,
+    #[serde(default)]
+    pub parse_time_secs: f64,
+    #[serde(default)]
+    pub max_parse_time_ms: f64,
+    /// Queued URLs left unfetched because robots.txt disallows them
+    #[serde(default)]
+    pub robots_disallowed_count: usize,
+}
+
+// The counters of `CrawlStats`, bumped by workers without taking a lock
+#[derive(Debug, Default)]
+struct StatsCounters {
+    success: AtomicUsize,
+    error: AtomicUsize,
+    not_modified: AtomicUsize,
+    parsed: AtomicUsize,
+    parse_micros: AtomicU64,
+    max_parse_micros: AtomicU64,
+    robots_disallowed: AtomicUsize,
 }
 
 #[derive(Debug, Clone)]
@@ -32,39 +82,116 @@ pub struct CrawlResult {
     pub graph: HashMap<String, Vec<String>>,
     pub total_links: usize,
     pub stats: CrawlStats,
+    /// The crawl was stopped (shutdown, timeout) before the frontier ran out
+    pub partial: bool,
+    /// Canonical URL -> URLs of the crawled pages declaring it
+    pub canonical_groups: HashMap<String, Vec<String>>,
+    /// Pages with identical or near-identical content, largest group first
+    pub duplicate_clusters: Vec<DuplicateCluster>,
+    /// Source URL -> linked URLs marked nofollow. These edges are in `graph`
+    /// too, whether or not they were followed.
+    pub nofollow_edges: HashMap<String, Vec<String>>,
+    /// Queued URLs left unfetched because robots.txt disallows them, sorted.
+    /// A streaming crawl reports them as `Skipped` events instead.
+    pub robots_disallowed: Vec<String>,
+}
+
+/// Asks a running crawl to stop: no new fetches are started, in-flight ones
+/// get `shutdown_grace_period` to finish, and `crawl` returns a partial
+/// result. Cheap to clone and safe to use from any task or signal handler.
+#[derive(Debug, Clone)]
+pub struct ShutdownHandle {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownHandle {
+    fn new() -> Self {
+        Self {
+            tx: Arc::new(watch::channel(false).0),
+        }
+    }
+
+    pub fn shutdown(&self) {
+        self.tx.send_replace(true);
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Resolves once shutdown has been requested (immediately if it already
+    /// has been).
+    pub async fn stopped(&self) {
+        let mut rx = self.tx.subscribe();
+        let _ = rx.wait_for(|stop| *stop).await;
+    }
 }
 
 pub struct Crawler {
-    visited: Arc<Mutex<HashSet<String>>>,
-    graph: Arc<Mutex<HashMap<String, Vec<String>>>>,
-    pages: Arc<Mutex<Vec<Page>>>,
+    visited: Arc<dyn VisitedStore>,
+    graph: Arc<DashMap<String, Vec<String>>>,
+    // Fetched pages by URL; the result lists them in the order they were
+    // crawled
+    pages: Arc<DashMap<String, Page>>,
+    // URLs robots.txt kept us from fetching, when not streaming
+    robots_disallowed: Arc<DashSet<String>>,
     config: CrawlerConfig,
     client: Client,
-    limiter: Arc<Semaphore>,
     robots_checker: RobotsChecker,
-    domain_counters: Arc<Mutex<HashMap<String, usize>>>,
+    parser: ParsePool,
+    domain_counters: Arc<DashMap<String, usize>>,
+    // Timing only; the counters live in `counters`
     stats: Arc<Mutex<CrawlStats>>,
+    counters: Arc<StatsCounters>,
+    scheduler: HostScheduler,
+    seeds: Arc<DashMap<String, Seed>>,
+    shutdown: ShutdownHandle,
+    // Set only on the crawler driving a `crawl_stream`
+    events: Option<mpsc::Sender<CrawlEvent>>,
+    frontier: Arc<Mutex<Box<dyn Frontier>>>,
+    // Signalled whenever pages are queued or the last in-flight page is done
+    frontier_ready: Arc<Notify>,
+    // Pages taken from the frontier (and sitemap sources) not finished yet
+    in_flight: Arc<AtomicUsize>,
+    // Pages taken from the frontier whose host wasn't due yet. They stay in
+    // flight and pending while parked. Looked at on every pop and never
+    // held across an await, so a plain mutex
+    parked: Arc<std::sync::Mutex<ParkedPages>>,
+    // Pages taken from the frontier and not finished yet, keyed by URL, for
+    // checkpointing
+    pending: Arc<DashMap<String, Page>>,
+    // Held shared while pages are marked visited and queued, and exclusively
+    // while a checkpoint is taken, so a snapshot never has a URL as visited
+    // but not yet queued
+    queue_gate: Arc<RwLock<()>>,
+    // Fingerprints of the pages fetched so far
+    duplicates: Arc<Mutex<DuplicateIndex>>,
+    // An earlier crawl to revalidate pages against
+    previous: Option<Arc<PreviousCrawl>>,
+    sinks: Vec<Arc<dyn OutputSink>>,
 }
 
 impl Crawler {
     pub fn new(config: CrawlerConfig) -> Result<Self> {
-        // Create HTTP client with proper settings
-        let client = Client::builder()
-            .user_agent(&config.user_agent)
-            .timeout(config.request_timeout)
-            .redirect(if config.follow_redirects {
-                reqwest::redirect::Policy::limited(10)
-            } else {
-                reqwest::redirect::Policy::none()
-            })
-            .build()
-            .map_err(CrawlerError::RequestError)?;
+        CrawlerBuilder::from_config(config).build()
+    }
 
-        // Store the concurrent_tasks value before moving config
-        let concurrent_tasks = config.concurrent_tasks;
+    pub fn builder() -> CrawlerBuilder {
+        CrawlerBuilder::new()
+    }
 
-        // Initialize robots.txt checker with the same client
-        let robots_checker = RobotsChecker::new(client.clone());
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_parts(
+        config: CrawlerConfig,
+        client: Client,
+        robots_checker: RobotsChecker,
+        frontier: Box<dyn Frontier>,
+        visited: Box<dyn VisitedStore>,
+        parser: ParsePool,
+        sinks: Vec<Arc<dyn OutputSink>>,
+        previous: Option<PreviousCrawl>,
+    ) -> Self {
+        let duplicates = DuplicateIndex::new(config.near_duplicate_distance);
 
         // Initialize stats
         let stats = Arc::new(Mutex::new(CrawlStats {
@@ -74,24 +201,65 @@ impl Crawler {
             success_count: 0,
             error_count: 0,
             avg_page_size: 0,
+            not_modified_count: 0,
+            parsed_count: 0,
+            parse_time_secs: 0.0,
+            max_parse_time_ms: 0.0,
+            robots_disallowed_count: 0,
         }));
 
-        Ok(Crawler {
-            visited: Arc::new(Mutex::new(HashSet::new())),
-            graph: Arc::new(Mutex::new(HashMap::new())),
-            pages: Arc::new(Mutex::new(Vec::new())),
-            config: config.clone(), // Clone the config here
+        Crawler {
+            visited: Arc::from(visited),
+            graph: Arc::new(DashMap::new()),
+            pages: Arc::new(DashMap::new()),
+            robots_disallowed: Arc::new(DashSet::new()),
+            config,
             client,
-            limiter: Arc::new(Semaphore::new(concurrent_tasks)),
             robots_checker,
-            domain_counters: Arc::new(Mutex::new(HashMap::new())),
+            parser,
+            domain_counters: Arc::new(DashMap::new()),
             stats,
-        })
+            counters: Arc::new(StatsCounters::default()),
+            scheduler: HostScheduler::new(),
+            seeds: Arc::new(DashMap::new()),
+            shutdown: ShutdownHandle::new(),
+            events: None,
+            frontier: Arc::new(Mutex::new(frontier)),
+            frontier_ready: Arc::new(Notify::new()),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            parked: Arc::new(std::sync::Mutex::new(ParkedPages::new())),
+            pending: Arc::new(DashMap::new()),
+            queue_gate: Arc::new(RwLock::new(())),
+            duplicates: Arc::new(Mutex::new(duplicates)),
+            previous: previous.map(Arc::new),
+            sinks,
+        }
+    }
+
+    /// A handle for stopping this crawler's crawl early.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    pub fn config(&self) -> &CrawlerConfig {
+        &self.config
     }
 
     pub async fn crawl(&self, start_url: &str) -> Result<CrawlResult> {
-        let start_time = Instant::now();
-        info!("🚀 Starting crawler at: {}", start_url);
+        self.crawl_seeds(vec![Seed::new(start_url)]).await
+    }
+
+    /// Crawl from several start URLs at once. Seeds from the config are
+    /// crawled as well; a URL listed twice is only crawled once.
+    pub async fn crawl_seeds(&self, seeds: Vec<Seed>) -> Result<CrawlResult> {
+        let seeds: Vec<Seed> = self.config.seeds.iter().cloned().chain(seeds).collect();
+        if seeds.is_empty() {
+            return Err(CrawlerError::ConfigError(
+                "No seed URLs to crawl".to_string(),
+            ));
+        }
+
+        info!("🚀 Starting crawler with {} seed(s)", seeds.len());
 
         // Update start time in stats
         {
@@ -99,259 +267,957 @@ impl Crawler {
             stats.started_at = Utc::now();
         }
 
-        // Create a channel for communication between workers
-        let (tx, mut rx) = mpsc::channel(100);
+        let mut start_pages = Vec::with_capacity(seeds.len());
+        for seed in seeds {
+            // Mark the start URL as visited right away, in the form links to
+            // it will take too
+            let mut urls = vec![seed.url.clone()];
+            if let Ok(url) = Url::parse(&seed.url) {
+                urls.push(self.normalize_url(&url));
+            }
+            if !self.mark_visited(urls).await[0] {
+                debug!("Skipping duplicate seed {}", seed.url);
+                continue;
+            }
 
-        // Create the starting point
-        let start = Page::new(start_url.to_string(), 0);
+            info!("   Seed: {}", seed.url);
+            start_pages.push(
+                Page::new(seed.url.clone(), 0).with_seed(Some(seed.url.clone()), seed.tag.clone()),
+            );
+            self.seeds.insert(seed.url.clone(), seed);
+        }
 
-        // Mark the start URL as visited right away
-        {
-            let mut visited = self.visited.lock().await;
-            visited.insert(start_url.to_string());
+        // Sitemaps are read in the background so crawling can start at once
+        let mut sources = Vec::new();
+        if self.config.use_sitemaps {
+            let crawler = self.clone();
+            let seeds = start_pages.clone();
+            self.in_flight.fetch_add(1, Ordering::SeqCst);
+            sources.push(tokio::spawn(async move {
+                crawler.enqueue_sitemap_urls(seeds).await;
+                crawler.finish_in_flight();
+            }));
         }
 
-        tx.send(start).await.map_err(|e| {
-            CrawlerError::ConfigError(format!("Failed to send initial page: {}", e))
-        })?;
+        self.run(start_pages, sources).await
+    }
 
-        // Set up worker tasks to process URLs
-        let mut handles = vec![];
+    /// Crawl like [`Crawler::crawl_seeds`], but report progress as a stream
+    /// of events instead of one result at the end.
+    ///
+    /// Fetched pages are handed to the stream rather than kept, so
+    /// memory use doesn't grow with the number of pages; the stream applies
+    /// backpressure if the consumer falls behind. Output sinks are not
+    /// written. The last event is always `Finished`, or `Error` without a
+    /// URL if the crawl could not run.
+    pub fn crawl_stream(&self, seeds: Vec<Seed>) -> CrawlEventStream {
+        let (tx, rx) = mpsc::channel(EVENT_BUFFER);
+
+        let mut crawler = self.clone();
+        crawler.events = Some(tx);
+
+        tokio::spawn(async move {
+            let event = match crawler.crawl_seeds(seeds).await {
+                Ok(result) => CrawlEvent::Finished(result.stats),
+                Err(e) => CrawlEvent::Error {
+                    url: None,
+                    error: e.to_string(),
+                },
+            };
+            crawler.emit(event).await;
+        });
 
-        // Main processing loop
-        loop {
+        CrawlEventStream::new(rx)
+    }
+
+    /// Continue a crawl from a checkpoint written by an earlier run.
+    ///
+    /// Pages that were queued or in flight when the checkpoint was taken are
+    /// fetched again; everything else is restored as-is.
+    pub async fn resume(&self, checkpoint: Checkpoint) -> Result<CrawlResult> {
+        info!(
+            "♻️  Resuming crawl from checkpoint taken at {} ({} pages done, {} pending)",
+            checkpoint.created_at,
+            checkpoint.pages.len(),
+            checkpoint.pending.len()
+        );
+
+        let seeds = self.restore_checkpoint(checkpoint).await;
+        self.run(seeds, Vec::new()).await
+    }
+
+    /// Crawl until the frontier is exhausted. `sources` are background tasks
+    /// that may still add to the frontier; each counts as in flight until it
+    /// is done, so the crawl doesn't finish before they do.
+    async fn run(
+        &self,
+        seeds: Vec<Page>,
+        sources: Vec<tokio::task::JoinHandle<()>>,
+    ) -> Result<CrawlResult> {
+        let start_time = Instant::now();
+        let previous_duration = self.stats.lock().await.duration_secs;
+
+        for page in seeds {
+            self.enqueue(page).await;
+        }
+
+        // Tells the workers to stop taking pages: on shutdown, on timeout, or
+        // once the URL limit is reached
+        let stop = ShutdownHandle::new();
+
+        // A fixed pool of workers pulls pages from the frontier, so the
+        // frontier, not task scheduling, decides the crawl order
+        let workers: Vec<_> = (0..self.config.concurrent_tasks.max(1))
+            .map(|_| {
+                let crawler = self.clone();
+                let stop = stop.clone();
+                tokio::spawn(async move { crawler.work(&stop).await })
+            })
+            .collect();
+        let abort_handles: Vec<_> = workers.iter().map(|h| h.abort_handle()).collect();
+        let workers_done = join_all(workers);
+        tokio::pin!(workers_done);
+
+        let mut checkpoint_timer = tokio::time::interval_at(
+            tokio::time::Instant::now() + self.config.checkpoint_interval,
+            self.config.checkpoint_interval,
+        );
+        let checkpointing = self.config.checkpoint_path.is_some();
+
+        let crawl_deadline = tokio::time::sleep(self.config.crawl_timeout);
+        tokio::pin!(crawl_deadline);
+
+        let mut partial = false;
+
+        // Workers exit on their own once the frontier is exhausted
+        let results = loop {
             tokio::select! {
-                // Try to receive a message with timeout
-                maybe_page = tokio::time::timeout(Duration::from_millis(100), rx.recv()) => {
-                    match maybe_page {
-                        // We received a page to process
-                        Ok(Some(page)) => {
-                            // Skip if we've reached max depth
-                            if page.depth >= self.config.max_depth {
-                                debug!("🛑 Reached max depth ({}) for {}", self.config.max_depth, page.url);
-                                continue;
-                            }
-
-                            // Check max URLs per domain limit
-                            if let Some(max_per_domain) = self.config.max_urls_per_domain {
-                                let domain = self.extract_domain(&page.url).unwrap_or_default();
-                                let mut domain_counters = self.domain_counters.lock().await;
-                                let count = domain_counters.entry(domain.clone()).or_insert(0);
-                                if *count >= max_per_domain {
-                                    debug!("Reached max URLs for domain {}: {}", domain, max_per_domain);
-                                    continue;
-                                }
-                                *count += 1;
-                            }
-
-                            // Check max total URLs limit
-                            if let Some(max_total) = self.config.max_total_urls {
-                                let visited_count = self.visited.lock().await.len();
-                                if visited_count >= max_total {
-                                    debug!("Reached max total URLs: {}", max_total);
-                                    break;
-                                }
-                            }
-
-                            // Clone what we need for the task
-                            let crawler = self.clone();
-                            let page_url = page.url.clone();
-                            let page_depth = page.depth;
-                            let tx = tx.clone();
-                            let page_clone = page.clone();
-
-                            // Spawn a new task to process this page
-                            let handle = tokio::spawn(async move {
-                                // Acquire a permit from the semaphore to limit concurrency
-                                let _permit = crawler.limiter.acquire().await.unwrap();
-
-                                info!("📊 Processing {} at depth {}/{}",
-                                    page_url, page_depth, crawler.config.max_depth);
-
-                                // Process the page and handle any links found
-                                match crawler.process_page(&page_clone).await {
-                                    Ok((processed_page, links)) => {
-                                        // Save the processed page
-                                        {
-                                            let mut pages = crawler.pages.lock().await;
-                                            pages.push(processed_page);
-                                        }
-
-                                        // Update the graph with new links
-                                        {
-                                            let mut graph = crawler.graph.lock().await;
-                                            graph.insert(page_url.clone(), links.clone());
-                                        }
-
-                                        // Update success stats
-                                        {
-                                            let mut stats = crawler.stats.lock().await;
-                                            stats.success_count += 1;
-                                        }
-
-                                        // Queue up new pages for processing
-                                        for link in links {
-                                            // Check if we've already visited this URL
-                                            let should_queue = {
-                                                let mut visited = crawler.visited.lock().await;
-                                                if !visited.contains(&link) {
-                                                    // Mark as visited preemptively
-                                                    visited.insert(link.clone());
-                                                    true
-                                                } else {
-                                                    false
-                                                }
-                                            };
-
-                                            // Check domain/path filtering
-                                            let allowed_domain = if !crawler.config.allowed_domains.is_empty() {
-                                                let domain = crawler.extract_domain(&link).unwrap_or_default();
-                                                crawler.config.allowed_domains.iter().any(|d| domain.contains(d))
-                                            } else {
-                                                true
-                                            };
-
-                                            let excluded_path = if !crawler.config.excluded_paths.is_empty() {
-                                                crawler.config.excluded_paths.iter().any(|p| link.contains(p))
-                                            } else {
-                                                false
-                                            };
-
-                                            if should_queue && allowed_domain && !excluded_path {
-                                                let new_page = Page::new(link.clone(), page_depth + 1);
-                                                debug!("➡️  Queueing {} (at depth {})", link, new_page.depth);
-
-                                                if tx.send(new_page).await.is_err() {
-                                                    warn!("❌ Channel closed, exiting");
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error!("⚠️  Error processing {}: {}", page_url, e);
-
-                                        // Update error stats
-                                        {
-                                            let mut stats = crawler.stats.lock().await;
-                                            stats.error_count += 1;
-                                        }
-                                    }
-                                }
-                            });
-
-                            handles.push(handle);
-
-                            // Clean up completed handles
-                            handles.retain(|h| !h.is_finished());
-                        },
-                        // Channel is empty for now, but might get more messages later
-                        Ok(None) => {
-                            // If all tasks are done and channel is empty, we're finished
-                            if handles.is_empty() {
-                                break;
-                            }
-
-                            // Otherwise wait a bit
-                            tokio::time::sleep(Duration::from_millis(10)).await;
-                        },
-                        // Timeout reached while waiting for new messages
-                        Err(_) => {
-                            // Check if any tasks are still running
-                            if handles.is_empty() {
-                                break;
-                            }
-
-                            // Clean up completed handles
-                            handles.retain(|h| !h.is_finished());
-                        }
-                    }
-                },
+                results = &mut workers_done => break Some(results),
+
+                // Periodically snapshot the crawl so it can be resumed
+                _ = checkpoint_timer.tick(), if checkpointing => {
+                    self.save_checkpoint().await;
+                }
 
                 // Check for a timeout on the entire crawl operation
-                _ = tokio::time::sleep(self.config.crawl_timeout) => {
+                _ = &mut crawl_deadline => {
                     info!("⚠️  Crawl timed out after {} seconds!", self.config.crawl_timeout.as_secs());
-                    break;
+                    partial = true;
+                    break None;
+                }
+
+                // Stop dispatching when asked to shut down
+                _ = self.shutdown.stopped() => {
+                    info!("🛑 Shutdown requested, no new fetches will be started");
+                    partial = true;
+                    break None;
                 }
             }
-        }
+        };
 
-        // Wait for remaining tasks to complete with timeout
-        if !handles.is_empty() {
-            match tokio::time::timeout(Duration::from_secs(5), join_all(handles)).await {
-                Ok(results) => {
-                    for result in results {
-                        if let Err(e) = result {
-                            error!("⚠️  A worker task failed: {}", e);
-                        }
+        // Let in-flight fetches finish within the grace period. Workers
+        // waiting for a page or a politeness slot bail out at once
+        let results = match results {
+            Some(results) => Some(results),
+            None => {
+                stop.shutdown();
+                let grace_period = self.config.shutdown_grace_period;
+                let in_flight = self.in_flight.load(Ordering::SeqCst);
+                if in_flight > 0 {
+                    info!(
+                        "⏳ Waiting up to {}s for {} in-flight page(s)",
+                        grace_period.as_secs_f64(),
+                        in_flight
+                    );
+                }
+                tokio::time::timeout(grace_period, &mut workers_done)
+                    .await
+                    .ok()
+            }
+        };
+
+        match results {
+            Some(results) => {
+                for result in results {
+                    if let Err(e) = result {
+                        error!("⚠️  A worker task failed: {}", e);
                     }
                 }
-                Err(_) => {
-                    warn!("Some tasks did not complete in time");
+            }
+            None => {
+                warn!("Some tasks did not complete in time, aborting them");
+                for handle in abort_handles {
+                    handle.abort();
                 }
+                partial = true;
             }
         }
 
-        info!("✅ Crawl completed successfully!");
+        // Sitemap discovery still running is no longer needed
+        for source in sources {
+            source.abort();
+        }
+
+        if partial {
+            info!("⚠️  Crawl stopped early, results are partial");
+        } else {
+            info!("✅ Crawl completed successfully!");
+        }
 
         // Update final stats
         {
             let mut stats = self.stats.lock().await;
             stats.finished_at = Utc::now();
-            stats.duration_secs = start_time.elapsed().as_secs_f64();
+            stats.duration_secs = previous_duration + start_time.elapsed().as_secs_f64();
+        }
+
+        // Leave a final checkpoint behind; if the crawl stopped early (timeout,
+        // URL limit) it still holds the unfinished frontier
+        if checkpointing {
+            self.save_checkpoint().await;
         }
 
         // Build the result
-        let pages = self.pages.lock().await.clone();
-        let graph = self.graph.lock().await.clone();
-        let stats = self.stats.lock().await.clone();
+        let pages = self.crawled_pages();
+        let mut graph = self.graph_snapshot();
+        let stats = self.stats_snapshot().await;
+
+        self.print_statistics(&graph, &stats);
+
+        let canonical_groups = canonical::canonical_groups(&pages);
+        let duplicate_clusters =
+            fingerprint::duplicate_clusters(&pages, self.config.near_duplicate_distance);
+        // Keyed like the graph, so the flags land on its edges
+        let content_url = |page: &Page| self.content_url(page);
+        let mut nofollow_edges = directives::nofollow_edges(&pages, content_url);
+        if self.config.collapse_canonical_duplicates {
+            graph = canonical::collapse_graph(&graph, &pages, content_url);
+            nofollow_edges = canonical::collapse_graph(&nofollow_edges, &pages, content_url);
+        }
 
         let total_links = graph.values().map(|v| v.len()).sum();
+        let mut robots_disallowed: Vec<String> = self
+            .robots_disallowed
+            .iter()
+            .map(|url| url.clone())
+            .collect();
+        robots_disallowed.sort();
 
-        self.print_statistics().await;
-
-        Ok(CrawlResult {
+        let result = CrawlResult {
             pages,
             graph,
             total_links,
             stats,
-        })
+            partial,
+            canonical_groups,
+            duplicate_clusters,
+            nofollow_edges,
+            robots_disallowed,
+        };
+
+        self.write_sinks(&result)?;
+
+        Ok(result)
     }
 
-    async fn process_page(&self, page: &Page) -> Result<(Page, Vec<String>)> {
-        debug!("📄 Crawling page: {}", page.url);
+    /// One worker of the pool: crawls pages from the frontier until there
+    /// are none left or `stop` is signalled. A page whose host isn't due yet
+    /// is parked rather than waited for, so the worker moves on to other
+    /// hosts meanwhile.
+    async fn work(&self, stop: &ShutdownHandle) {
+        while let Some(page) = self.next_page(stop).await {
+            match self.crawl_page(page, stop).await {
+                Some((host, due, page)) => self.parked.lock().unwrap().park(host, due, page),
+                None => self.finish_in_flight(),
+            }
+        }
+    }
 
-        // Check robots.txt before processing
-        if self.config.respect_robots_txt {
-            if !self.should_crawl_url(&page.url).await {
-                return Ok((
-                    Page::new(page.url.clone(), page.depth)
-                        .with_status_code(403)
-                        .mark_crawled(),
-                    Vec::new(),
-                ));
+    /// Wait for the next page to crawl and count it as in flight: a parked
+    /// page whose host is due again, or else the next page in the frontier.
+    /// Returns `None` once the frontier is empty and nothing in flight can
+    /// add to it any more, or when stopped.
+    async fn next_page(&self, stop: &ShutdownHandle) -> Option<Page> {
+        loop {
+            // Register for wake-ups before looking, so a page queued right
+            // after we find the frontier empty isn't missed
+            let ready = self.frontier_ready.notified();
+            tokio::pin!(ready);
+            ready.as_mut().enable();
+
+            if stop.is_shutdown() {
+                return None;
+            }
+
+            // Parked pages are already in flight and pending
+            let (next_due, parked_full) = {
+                let mut parked = self.parked.lock().unwrap();
+                if let Some(page) = parked.take_due(tokio::time::Instant::now()) {
+                    return Some(page);
+                }
+                (parked.next_due(), parked.len() >= MAX_PARKED_PAGES)
+            };
+
+            {
+                let mut frontier = self.frontier.lock().await;
+                // With too many pages parked, wait for their hosts instead
+                if let Some(page) = (!parked_full).then(|| frontier.pop()).flatten() {
+                    // Counted under the frontier lock: a worker that finds
+                    // the frontier empty sees this page in flight instead.
+                    // It turns pending under the lock too, so a checkpoint
+                    // always has it either queued or pending
+                    self.in_flight.fetch_add(1, Ordering::SeqCst);
+                    self.pending.insert(page.url.clone(), page.clone());
+
+                    // A host with pages parked gets to this one after them.
+                    // Most pops find nothing parked and skip parsing the URL
+                    let mut parked = self.parked.lock().unwrap();
+                    if parked.is_empty() {
+                        return Some(page);
+                    }
+                    let Some(host) = politeness::host_key(&page.url) else {
+                        return Some(page);
+                    };
+                    match parked.line_up(&host, page) {
+                        Some(page) => return Some(page),
+                        None => continue,
+                    }
+                }
+
+                // A page's links are queued before it stops counting as in
+                // flight, so an empty frontier with nothing in flight stays
+                // empty: the crawl is done
+                if self.in_flight.load(Ordering::SeqCst) == 0 {
+                    self.frontier_ready.notify_waiters();
+                    return None;
+                }
+            }
+
+            let parked_due = async {
+                match next_due {
+                    Some(due) => tokio::time::sleep_until(due).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                _ = &mut ready => {}
+                _ = parked_due => {}
+                _ = stop.stopped() => return None,
+            }
+        }
+    }
+
+    /// Mark one page (or background source) as done, waking idle workers
+    /// when it was the last one so they can see the crawl is over.
+    fn finish_in_flight(&self) {
+        if self.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.frontier_ready.notify_waiters();
+        }
+    }
+
+    /// Fetch and record one page taken from the frontier, queueing the links
+    /// it leads to. If the page's host isn't due yet, nothing is fetched and
+    /// the page is handed back with its host and when the host is due.
+    async fn crawl_page(
+        &self,
+        page: Page,
+        stop: &ShutdownHandle,
+    ) -> Option<(String, tokio::time::Instant, Page)> {
+        // Skip if we've reached max depth
+        let max_depth = self.depth_limit(&page).await;
+        if page.depth >= max_depth {
+            debug!("🛑 Reached max depth ({}) for {}", max_depth, page.url);
+            self.pending.remove(&page.url);
+            self.emit_skipped(&page.url, SkipReason::MaxDepth).await;
+            return None;
+        }
+
+        // Check max URLs per domain limit
+        if let Some(max_per_domain) = self.config.max_urls_per_domain {
+            let domain = self.extract_domain(&page.url).unwrap_or_default();
+            let within_limit = {
+                let mut count = self.domain_counters.entry(domain.clone()).or_insert(0);
+                if *count < max_per_domain {
+                    *count += 1;
+                    true
+                } else {
+                    false
+                }
+            };
+            if !within_limit {
+                debug!("Reached max URLs for domain {}: {}", domain, max_per_domain);
+                self.pending.remove(&page.url);
+                self.emit_skipped(&page.url, SkipReason::MaxUrlsPerDomain)
+                    .await;
+                return None;
+            }
+        }
+
+        // Check max total URLs limit; the page stays pending for a checkpoint
+        if let Some(max_total) = self.config.max_total_urls {
+            let visited_count = self.visited.len();
+            if visited_count >= max_total {
+                debug!("Reached max total URLs: {}", max_total);
+                self.emit_skipped(&page.url, SkipReason::MaxTotalUrls).await;
+                stop.shutdown();
+                return None;
+            }
+        }
+
+        // Check robots.txt before fetching
+        if self.config.respect_robots_txt && !self.should_crawl_url(&page.url).await {
+            self.pending.remove(&page.url);
+            self.counters
+                .robots_disallowed
+                .fetch_add(1, Ordering::Relaxed);
+            // Stream consumers get the event; everyone else finds them in
+            // the result
+            if self.events.is_none() {
+                self.robots_disallowed.insert(page.url.clone());
+            }
+            self.emit_skipped(&page.url, SkipReason::RobotsTxt).await;
+            return None;
+        }
+
+        // Take this host's politeness slot, or hand the page back to wait
+        // for it without holding up the worker
+        if let Some(host) = politeness::host_key(&page.url) {
+            let delay = self.politeness_delay(&page.url, &host).await;
+            if let Err(due) = self.scheduler.try_take_turn(&host, delay).await {
+                // Not fetched after all, so it doesn't count for its domain
+                // yet
+                if self.config.max_urls_per_domain.is_some() {
+                    let domain = self.extract_domain(&page.url).unwrap_or_default();
+                    if let Some(mut count) = self.domain_counters.get_mut(&domain) {
+                        *count = count.saturating_sub(1);
+                    }
+                }
+                return Some((host, due, page));
+            }
+        }
+
+        info!(
+            "📊 Processing {} at depth {}/{}",
+            page.url, page.depth, max_depth
+        );
+
+        // Process the page and handle any links found
+        match self.process_page(&page).await {
+            Ok((processed_page, links)) => {
+                let not_modified = processed_page.not_modified;
+                // Queue up new pages for processing. This comes first: once
+                // the page is in the graph, a checkpoint counts it as done
+                let new_pages = links.into_iter().map(|link| {
+                    Page::new(link, page.depth + 1)
+                        .with_seed(page.seed.clone(), page.seed_tag.clone())
+                });
+                let queued = self.enqueue_new(new_pages).await;
+
+                // Update the graph with redirect edges and new links
+                self.record_edges(&processed_page, &processed_page.links);
+
+                // Save the processed page, or hand it straight to the stream
+                // consumer when streaming
+                if self.config.robots_directives.excludes(&processed_page) {
+                    debug!("🙈 {} is noindex, leaving it out", processed_page.url);
+                    self.emit_skipped(&processed_page.url, SkipReason::Noindex)
+                        .await;
+                } else if self.events.is_some() {
+                    self.emit(CrawlEvent::PageFetched(processed_page)).await;
+                } else {
+                    self.pages
+                        .insert(processed_page.url.clone(), processed_page);
+                }
+
+                // Update success stats
+                self.counters.success.fetch_add(1, Ordering::Relaxed);
+                if not_modified {
+                    self.counters.not_modified.fetch_add(1, Ordering::Relaxed);
+                }
+
+                for url in queued {
+                    self.emit(CrawlEvent::LinkDiscovered {
+                        from: page.url.clone(),
+                        url,
+                        depth: page.depth + 1,
+                    })
+                    .await;
+                }
+            }
+            Err(e) => {
+                error!("⚠️  Error processing {}: {}", page.url, e);
+                self.emit(CrawlEvent::Error {
+                    url: Some(page.url.clone()),
+                    error: e.to_string(),
+                })
+                .await;
+
+                // Update error stats
+                self.counters.error.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        // Only now is the page done: its links are queued
+        self.pending.remove(&page.url);
+        None
+    }
+
+    /// The depth limit for a page: its seed's own limit if it has one,
+    /// otherwise the configured maximum.
+    async fn depth_limit(&self, page: &Page) -> u32 {
+        let Some(seed_url) = &page.seed else {
+            return self.config.max_depth;
+        };
+
+        self.seeds
+            .get(seed_url)
+            .and_then(|seed| seed.max_depth)
+            .unwrap_or(self.config.max_depth)
+    }
+
+    /// Queue newly discovered pages, leaving out those seen before or
+    /// outside the allowed domains / excluded paths. Returns the URLs
+    /// queued.
+    async fn enqueue_new(&self, pages: impl IntoIterator<Item = Page>) -> Vec<String> {
+        let pages: Vec<Page> = pages
+            .into_iter()
+            .filter(|page| self.in_scope(&page.url))
+            .collect();
+        if pages.is_empty() {
+            return Vec::new();
+        }
+
+        // A checkpoint waits until the pages marked visited here are queued
+        let _gate = self.queue_gate.read().await;
+
+        // Check if we've already visited each URL, marking it preemptively
+        let urls = pages.iter().map(|page| page.url.clone()).collect();
+        let new = self.mark_visited(urls).await;
+        let new_pages: Vec<Page> = pages
+            .into_iter()
+            .zip(new)
+            .filter_map(|(page, new)| new.then_some(page))
+            .collect();
+        if new_pages.is_empty() {
+            return Vec::new();
+        }
+
+        let mut queued = Vec::with_capacity(new_pages.len());
+        {
+            let mut frontier = self.frontier.lock().await;
+            for page in new_pages {
+                debug!("➡️  Queueing {} (at depth {})", page.url, page.depth);
+                queued.push(page.url.clone());
+                frontier.push(page);
             }
         }
 
-        // Make an HTTP request
-        let response = self.client.get(&page.url).send().await?;
+        for _ in &queued {
+            self.frontier_ready.notify_one();
+        }
+        queued
+    }
+
+    /// Whether a URL passes the allowed-domain and excluded-path filters.
+    fn in_scope(&self, url: &str) -> bool {
+        // Check domain/path filtering
+        let allowed_domain = if !self.config.allowed_domains.is_empty() {
+            let domain = self.extract_domain(url).unwrap_or_default();
+            self.config
+                .allowed_domains
+                .iter()
+                .any(|d| domain.contains(d))
+        } else {
+            true
+        };
+
+        let excluded_path = self.config.excluded_paths.iter().any(|p| url.contains(p));
+
+        allowed_domain && !excluded_path
+    }
+
+    /// Add a fetched page to the link graph. Each redirect hop becomes an
+    /// edge to its target, and the page's links hang off the final URL.
+    fn record_edges(&self, page: &Page, links: &[String]) {
+        let normalize = |url: &str| {
+            Url::parse(url)
+                .map(|u| self.normalize_url(&u))
+                .unwrap_or_else(|_| url.to_string())
+        };
+
+        for hop in &page.redirects {
+            let from = if hop.url == page.url {
+                page.url.clone()
+            } else {
+                normalize(&hop.url)
+            };
+            self.graph.insert(from, vec![normalize(&hop.location)]);
+        }
+
+        let content_url = self.content_url(page);
+
+        // A redirect onto a page we already have adds only the redirect edges
+        if content_url == page.url || !links.is_empty() || !self.graph.contains_key(&content_url) {
+            self.graph.insert(content_url, links.to_vec());
+        }
+    }
+
+    // The graph node a page's own links hang off: where its content was
+    // served from
+    fn content_url(&self, page: &Page) -> String {
+        match &page.final_url {
+            Some(final_url) => Url::parse(final_url)
+                .map(|u| self.normalize_url(&u))
+                .unwrap_or_else(|_| final_url.clone()),
+            None => page.url.clone(),
+        }
+    }
+
+    /// Queue the URLs listed in the sitemaps of each seed's site. Sitemap
+    /// pages count as one hop from their seed.
+    async fn enqueue_sitemap_urls(&self, seeds: Vec<Page>) {
+        let mut sites = HashSet::new();
+
+        for seed in seeds {
+            let Some(site) = Url::parse(&seed.url).ok().map(|u| u.origin()) else {
+                continue;
+            };
+            if !sites.insert(site) {
+                continue;
+            }
+
+            // Sitemaps are fetched like robots.txt, letting the client
+            // follow redirects
+            let fetcher = SitemapFetcher::new(
+                self.robots_checker.client(),
+                &self.robots_checker,
+                self.config.max_sitemap_urls,
+            );
+
+            let entries = match fetcher.discover(&seed.url).await {
+                Ok(entries) => entries,
+                Err(e) => {
+                    warn!("⚠️  Sitemap discovery failed for {}: {}", seed.url, e);
+                    continue;
+                }
+            };
+
+            let pages = entries.into_iter().filter_map(|entry| {
+                let url = Url::parse(&entry.loc).ok()?;
+                Some(
+                    Page::new(self.normalize_url(&url), 1)
+                        .with_seed(seed.seed.clone(), seed.seed_tag.clone())
+                        .with_sitemap_entry(entry.lastmod, entry.priority),
+                )
+            });
+            let queued = self.enqueue_new(pages).await.len();
+
+            info!("🗺️  Queued {} URLs from sitemaps of {}", queued, seed.url);
+        }
+    }
+
+    /// Mark URLs as seen, returning whether each was new. A store that
+    /// touches disk is called from the blocking pool, so lookups and batch
+    /// commits don't hold up the async workers.
+    async fn mark_visited(&self, urls: Vec<String>) -> Vec<bool> {
+        if urls.is_empty() || !self.visited.is_blocking() {
+            return self.visited.insert_all(&urls);
+        }
+
+        let visited = Arc::clone(&self.visited);
+        let count = urls.len();
+        tokio::task::spawn_blocking(move || visited.insert_all(&urls))
+            .await
+            .unwrap_or_else(|e| {
+                error!("Visited store task failed: {}", e);
+                vec![true; count]
+            })
+    }
+
+    /// Hand a page to the frontier.
+    async fn enqueue(&self, page: Page) {
+        self.frontier.lock().await.push(page);
+        self.frontier_ready.notify_one();
+    }
+
+    /// Take a consistent snapshot of the crawl state.
+    ///
+    /// The queue gate is held throughout, so no links are being queued
+    /// meanwhile, and pages move from the frontier to `pending` under the
+    /// frontier lock. Workers queue a page's links before adding it to the
+    /// graph and remove it from `pending` last, so every page shows up
+    /// either as finished or as pending (possibly both, never neither).
+    pub async fn checkpoint(&self) -> Checkpoint {
+        let _gate = self.queue_gate.write().await;
+        let (queued, pending) = {
+            let frontier = self.frontier.lock().await;
+            let pending: Vec<Page> = self.pending.iter().map(|p| p.value().clone()).collect();
+            (frontier.pages(), pending)
+        };
+        // A store that can't list its URLs leaves this empty; restoring
+        // rebuilds it from the graph and the pending pages. One that keeps
+        // its URLs on disk is left out too: listing it would pull the whole
+        // set into memory, and a resumed crawl reopens it anyway
+        let visited = if self.visited.persists() {
+            Vec::new()
+        } else {
+            self.visited.urls().unwrap_or_default()
+        };
+        let graph = self.graph_snapshot();
+        let pages = self.crawled_pages();
+        let domain_counters = self
+            .domain_counters
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect();
+        let stats = self.stats_snapshot().await;
+        let seeds = self.seeds.iter().map(|seed| seed.value().clone()).collect();
+        let robots_disallowed = self
+            .robots_disallowed
+            .iter()
+            .map(|url| url.clone())
+            .collect();
+
+        Checkpoint {
+            created_at: Utc::now(),
+            visited,
+            pending: pending.into_iter().chain(queued).collect(),
+            graph,
+            pages,
+            domain_counters,
+            stats,
+            seeds,
+            robots_disallowed,
+        }
+    }
+
+    async fn save_checkpoint(&self) {
+        let Some(path) = &self.config.checkpoint_path else {
+            return;
+        };
+
+        let checkpoint = self.checkpoint().await;
+        match checkpoint::save_checkpoint(&checkpoint, path) {
+            Ok(()) => debug!(
+                "💾 Checkpoint written to {} ({} pending)",
+                path,
+                checkpoint.pending.len()
+            ),
+            Err(e) => warn!("⚠️  Failed to write checkpoint to {}: {}", path, e),
+        }
+    }
+
+    /// Load checkpointed state into this crawler and return the pages that
+    /// still need to be fetched.
+    async fn restore_checkpoint(&self, checkpoint: Checkpoint) -> Vec<Page> {
+        // A page can be both finished and pending if the snapshot raced with
+        // its worker; don't fetch it twice
+        let seeds: Vec<Page> = checkpoint
+            .pending
+            .into_iter()
+            .filter(|page| !checkpoint.graph.contains_key(&page.url))
+            .collect();
+
+        {
+            let mut duplicates = self.duplicates.lock().await;
+            for page in &checkpoint.pages {
+                if let Some(hash) = &page.content_hash {
+                    duplicates.insert(&page.url, hash, page.simhash);
+                }
+            }
+        }
+
+        let graph_urls = checkpoint
+            .graph
+            .iter()
+            .flat_map(|(url, links)| std::iter::once(url).chain(links));
+        let pending_urls = seeds.iter().map(|page| &page.url);
+        let urls = checkpoint
+            .visited
+            .iter()
+            .chain(graph_urls)
+            .chain(pending_urls)
+            .cloned()
+            .collect();
+        self.mark_visited(urls).await;
+
+        self.graph.clear();
+        for (url, links) in checkpoint.graph {
+            self.graph.insert(url, links);
+        }
+        self.pages.clear();
+        for page in checkpoint.pages {
+            self.pages.insert(page.url.clone(), page);
+        }
+        self.domain_counters.clear();
+        for (domain, count) in checkpoint.domain_counters {
+            self.domain_counters.insert(domain, count);
+        }
+        self.counters
+            .success
+            .store(checkpoint.stats.success_count, Ordering::Relaxed);
+        self.counters
+            .error
+            .store(checkpoint.stats.error_count, Ordering::Relaxed);
+        self.counters
+            .not_modified
+            .store(checkpoint.stats.not_modified_count, Ordering::Relaxed);
+        self.counters
+            .parsed
+            .store(checkpoint.stats.parsed_count, Ordering::Relaxed);
+        self.counters.parse_micros.store(
+            (checkpoint.stats.parse_time_secs * 1e6) as u64,
+            Ordering::Relaxed,
+        );
+        self.counters.max_parse_micros.store(
+            (checkpoint.stats.max_parse_time_ms * 1e3) as u64,
+            Ordering::Relaxed,
+        );
+        self.counters
+            .robots_disallowed
+            .store(checkpoint.stats.robots_disallowed_count, Ordering::Relaxed);
+        *self.stats.lock().await = checkpoint.stats;
+        self.robots_disallowed.clear();
+        for url in checkpoint.robots_disallowed {
+            self.robots_disallowed.insert(url);
+        }
+        self.seeds.clear();
+        for seed in checkpoint.seeds {
+            self.seeds.insert(seed.url.clone(), seed);
+        }
+
+        seeds
+    }
+
+    /// The fetched pages, in the order they were crawled.
+    fn crawled_pages(&self) -> Vec<Page> {
+        let mut pages: Vec<Page> = self.pages.iter().map(|p| p.value().clone()).collect();
+        pages.sort_by(|a, b| a.crawled_at.cmp(&b.crawled_at).then(a.url.cmp(&b.url)));
+        pages
+    }
+
+    fn graph_snapshot(&self) -> HashMap<String, Vec<String>> {
+        self.graph
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
+    async fn stats_snapshot(&self) -> CrawlStats {
+        let mut stats = self.stats.lock().await.clone();
+        stats.success_count = self.counters.success.load(Ordering::Relaxed);
+        stats.error_count = self.counters.error.load(Ordering::Relaxed);
+        stats.not_modified_count = self.counters.not_modified.load(Ordering::Relaxed);
+        stats.parsed_count = self.counters.parsed.load(Ordering::Relaxed);
+        stats.parse_time_secs = self.counters.parse_micros.load(Ordering::Relaxed) as f64 / 1e6;
+        stats.max_parse_time_ms =
+            self.counters.max_parse_micros.load(Ordering::Relaxed) as f64 / 1e3;
+        stats.robots_disallowed_count = self.counters.robots_disallowed.load(Ordering::Relaxed);
+        stats
+    }
+
+    /// Send an event to the stream consumer, if there is one. If the stream
+    /// has been dropped, nobody wants the rest of the crawl: shut it down.
+    async fn emit(&self, event: CrawlEvent) {
+        if let Some(tx) = &self.events {
+            if tx.send(event).await.is_err() {
+                self.shutdown.shutdown();
+            }
+        }
+    }
+
+    async fn emit_skipped(&self, url: &str, reason: SkipReason) {
+        if self.events.is_some() {
+            self.emit(CrawlEvent::Skipped {
+                url: url.to_string(),
+                reason,
+            })
+            .await;
+        }
+    }
+
+    fn write_sinks(&self, result: &CrawlResult) -> Result<()> {
+        // Streaming consumers already got every page
+        if self.events.is_some() {
+            return Ok(());
+        }
+
+        for sink in &self.sinks {
+            sink.write(result)?;
+        }
+        Ok(())
+    }
+
+    async fn process_page(&self, page: &Page) -> Result<(Page, Vec<String>)> {
+        debug!("📄 Crawling page: {}", page.url);
+
+        // Make an HTTP request, retrying per the retry policy and following
+        // redirects ourselves so every hop is recorded
+        let (response, attempts, redirects) = self.fetch_following_redirects(&page.url).await?;
         let status = response.status();
         let status_code = status.as_u16();
 
+        let final_url = response.url().clone();
+        // Only a followed redirect moves the page; the request URL itself
+        // may serialize differently (a root URL gains its trailing slash)
+        let moved =
+            !redirects.is_empty() && Url::parse(&page.url).ok().as_ref() != Some(&final_url);
+        let (etag, last_modified) = validators(response.headers());
+        let fetched = page
+            .blank_copy()
+            .with_attempts(attempts)
+            .with_redirects(redirects, moved.then(|| final_url.to_string()))
+            .with_validators(etag, last_modified);
+
+        // Unchanged since the previous crawl: what we stored still holds
+        let unchanged = match (&self.previous, status) {
+            (Some(previous), StatusCode::NOT_MODIFIED) => previous.get(final_url.as_str()),
+            _ => None,
+        };
+
         // Check for successful response
-        if !status.is_success() {
+        if !status.is_success() && unchanged.is_none() {
             warn!(
                 "⚠️  Failed to download page: {} (status: {})",
                 page.url, status
             );
             return Ok((
-                Page::new(page.url.clone(), page.depth)
-                    .with_status_code(status_code)
-                    .mark_crawled(),
+                fetched.with_status_code(status_code).mark_crawled(),
                 Vec::new(),
             ));
         }
 
+        // If we were redirected, the content belongs to the final URL: don't
+        // process it twice when that URL is crawled (or queued) on its own
+        if !fetched.redirects.is_empty() {
+            let final_normalized = self.normalize_url(&final_url);
+            let elsewhere = final_normalized != page.url;
+            let mut urls: Vec<String> = elsewhere.then_some(final_normalized).into_iter().collect();
+            urls.extend(
+                fetched
+                    .redirects
+                    .iter()
+                    .filter_map(|hop| Url::parse(&hop.location).ok())
+                    .map(|location| self.normalize_url(&location)),
+            );
+            let new = self.mark_visited(urls).await;
+            let already_seen = elsewhere && !new[0];
+
+            if already_seen {
+                debug!("↪️  {} redirects to already-seen {}", page.url, final_url);
+                return Ok((
+                    fetched.with_status_code(status_code).mark_crawled(),
+                    Vec::new(),
+                ));
+            }
+        }
+
+        if let Some(stored) = unchanged {
+            debug!("♻️  {} not modified, reusing the previous crawl", page.url);
+            return Ok(self.reuse_stored_page(fetched, stored).await);
+        }
+
         // Get content type
         let content_type = response
             .headers()
@@ -360,107 +1226,321 @@ impl Crawler {
             .unwrap_or("")
             .to_string();
 
+        // X-Robots-Tag can come with any content type; meta tags are read
+        // once the HTML is parsed
+        let headers = response.headers().clone();
+        let policies = &self.config.robots_directives;
+
         // Skip non-HTML content
         if !content_type.contains("text/html") {
             debug!("Skipping non-HTML content: {} ({})", page.url, content_type);
+            let robots_directives = policies.filter(directives::page_directives(
+                &headers,
+                &[],
+                &self.config.user_agent,
+            ));
             return Ok((
-                Page::new(page.url.clone(), page.depth)
+                fetched
                     .with_status_code(status_code)
                     .with_content_type(content_type)
+                    .with_directives(robots_directives, Vec::new())
                     .mark_crawled(),
                 Vec::new(),
             ));
         }
 
-        // Get the response text first
         let bytes = response.bytes().await?;
-        let text = String::from_utf8_lossy(&bytes);
         let size = bytes.len();
 
-        // Extract links without any async operations in between
-        // NOTE: This is the key fix for the Send issue
-        let (links, title) = self.extract_links_and_title(&text, &page.url)?;
-
-        // Get delay for the domain if needed
-        if let Ok(domain) = self.extract_domain(&page.url) {
-            if let Some(delay) = self
-                .robots_checker
-                .get_crawl_delay(&domain, &self.config.user_agent)
+        // Parse on the parser pool, off the async runtime. Relative links
+        // resolve against where we ended up, not where we started
+        let (content, parse_time) = self
+            .parser
+            .parse(bytes, final_url, self.config.normalization.clone())
+            .await?;
+        debug!(
+            "Parsed {} in {:.2}ms",
+            page.url,
+            parse_time.as_secs_f64() * 1000.0
+        );
+
+        let parse_micros = parse_time.as_micros() as u64;
+        self.counters.parsed.fetch_add(1, Ordering::Relaxed);
+        self.counters
+            .parse_micros
+            .fetch_add(parse_micros, Ordering::Relaxed);
+        self.counters
+            .max_parse_micros
+            .fetch_max(parse_micros, Ordering::Relaxed);
+
+        let duplicate_of =
+            self.duplicates
+                .lock()
                 .await
-            {
-                // Use the larger of robots.txt delay and our configured delay
-                let configured_delay = self.config.delay_between_requests;
-                let actual_delay = if delay > configured_delay {
-                    delay
-                } else {
-                    configured_delay
-                };
-
-                debug!(
-                    "Sleeping for {}ms (robots.txt crawl-delay)",
-                    actual_delay.as_millis()
-                );
-                tokio::time::sleep(actual_delay).await;
-            } else {
-                // Use our configured delay
-                tokio::time::sleep(self.config.delay_between_requests).await;
-            }
+                .insert(&page.url, &content.content_hash, content.simhash);
+        if let Some(original) = &duplicate_of {
+            debug!("🪞 {} duplicates {}", page.url, original);
         }
 
+        let robots_directives = policies.filter(directives::page_directives(
+            &headers,
+            &content.meta,
+            &self.config.user_agent,
+        ));
+        let nofollow_links = if policies.link_nofollow.records() {
+            content.nofollow_links
+        } else {
+            Vec::new()
+        };
+
         // Create the updated page with all information
-        let processed_page = Page::new(page.url.clone(), page.depth)
-            .with_links(links.clone())
+        let mut processed_page = fetched
+            .with_links(content.links)
             .with_status_code(status_code)
             .with_content_type(content_type)
             .with_size(size)
+            .with_canonical(content.canonical)
+            .with_fingerprint(content.content_hash, content.simhash, duplicate_of)
+            .with_parse_time(parse_time)
+            .with_directives(robots_directives, nofollow_links)
             .mark_crawled();
 
-        if let Some(t) = title {
-            Ok((processed_page.with_title(t), links))
-        } else {
-            Ok((processed_page, links))
+        if let Some(t) = content.title {
+            processed_page = processed_page.with_title(t);
+        }
+
+        // The links are recorded, but a copy's pages are the original's pages
+        if processed_page.duplicate_of.is_some() && self.config.skip_duplicate_links {
+            return Ok((processed_page, Vec::new()));
+        }
+
+        let links = self.links_to_follow(&processed_page);
+        Ok((processed_page, links))
+    }
+
+    /// The page's links minus those its nofollow directives rule out; they
+    /// stay in the graph all the same.
+    fn links_to_follow(&self, page: &Page) -> Vec<String> {
+        let links = self.config.robots_directives.links_to_follow(page);
+        if links.len() < page.links.len() {
+            debug!(
+                "🚷 Not following {} nofollow link(s) on {}",
+                page.links.len() - links.len(),
+                page.url
+            );
+        }
+        links
+    }
+
+    /// Rebuild a page the server reported unchanged from the previous crawl.
+    async fn reuse_stored_page(&self, fetched: Page, stored: &StoredPage) -> (Page, Vec<String>) {
+        let mut page = fetched
+            .with_links(stored.links.clone())
+            .with_canonical(stored.canonical.clone())
+            .mark_crawled();
+
+        page.status_code = stored.status_code;
+        page.title = stored.title.clone();
+        page.content_type = stored.content_type.clone();
+        page.size = stored.size_bytes;
+        page.not_modified = true;
+        // A 304 need not repeat the validators
+        page.etag = page.etag.or_else(|| stored.etag.clone());
+        page.last_modified = page.last_modified.or_else(|| stored.last_modified.clone());
+        page.robots_directives = stored.robots_directives.clone();
+        page.nofollow_links = stored.nofollow_links.clone();
+
+        if let Some(content_hash) = &stored.content_hash {
+            let duplicate_of =
+                self.duplicates
+                    .lock()
+                    .await
+                    .insert(&page.url, content_hash, stored.simhash);
+            page = page.with_fingerprint(content_hash.clone(), stored.simhash, duplicate_of);
         }
+
+        if page.duplicate_of.is_some() && self.config.skip_duplicate_links {
+            return (page, Vec::new());
+        }
+
+        let links = self.links_to_follow(&page);
+        (page, links)
     }
 
-    // New helper method to extract links without async calls
-    // This ensures we don't have `Html` across an await point
-    fn extract_links_and_title(
+    /// GET `url`, following up to `max_redirects` redirects and recording
+    /// each hop. Following stops early at a loop, at a target outside the
+    /// allowed domains or excluded by robots.txt, or when redirects are
+    /// disabled; the 3xx response is returned in that case.
+    async fn fetch_following_redirects(
         &self,
-        html_text: &str,
-        base_url_str: &str,
-    ) -> Result<(Vec<String>, Option<String>)> {
-        // Parse HTML and extract links
-        let document = Html::parse_document(html_text);
-
-        // Extract page title
-        let title = document
-            .select(&Selector::parse("title").unwrap())
-            .next()
-            .and_then(|el| el.text().next())
-            .map(|s| s.to_string());
-
-        let base_url = Url::parse(base_url_str)?;
-        let selector = Selector::parse("a[href]").unwrap();
-
-        // Extract and validate links
-        let mut links = Vec::new();
-
-        for element in document.select(&selector) {
-            if let Some(href) = element.value().attr("href") {
-                // Convert relative URLs to absolute
-                if let Ok(absolute_url) = base_url.join(href) {
-                    // Only accept HTTP(S) links
-                    if absolute_url.scheme() == "http" || absolute_url.scheme() == "https" {
-                        // Normalize the URL to avoid duplicates
-                        let normalized_url = self.normalize_url(&absolute_url);
-                        links.push(normalized_url);
-                    }
+        url: &str,
+    ) -> Result<(reqwest::Response, u32, Vec<RedirectHop>)> {
+        let mut current = url.to_string();
+        let mut redirects = Vec::new();
+        let mut total_attempts = 0;
+
+        loop {
+            // The first request has its slot already; each hop after it
+            // waits its turn on its own host
+            if !redirects.is_empty() {
+                if let Some(host) = politeness::host_key(&current) {
+                    let delay = self.politeness_delay(&current, &host).await;
+                    self.scheduler.wait_turn(&host, delay).await;
+                }
+            }
+
+            let (response, attempts) = self.fetch_with_retry(&current).await?;
+            total_attempts += attempts;
+
+            let status = response.status();
+            if !status.is_redirection() {
+                return Ok((response, total_attempts, redirects));
+            }
+
+            // 304 and friends carry no Location; nothing to follow
+            let Some(location) = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|h| h.to_str().ok())
+                .and_then(|location| response.url().join(location).ok())
+            else {
+                return Ok((response, total_attempts, redirects));
+            };
+
+            redirects.push(RedirectHop {
+                url: current.clone(),
+                status: status.as_u16(),
+                location: location.to_string(),
+            });
+
+            if !self.config.follow_redirects {
+                return Ok((response, total_attempts, redirects));
+            }
+
+            if redirects.iter().any(|hop| hop.url == location.as_str()) {
+                warn!("🔄 Redirect loop: {} -> {}", url, location);
+                return Ok((response, total_attempts, redirects));
+            }
+
+            if redirects.len() > self.config.max_redirects {
+                warn!("⚠️  Too many redirects from {}", url);
+                return Ok((response, total_attempts, redirects));
+            }
+
+            if !self.in_scope(location.as_str())
+                || (self.config.respect_robots_txt
+                    && !self.should_crawl_url(location.as_str()).await)
+            {
+                debug!("Not following redirect to {}", location);
+                return Ok((response, total_attempts, redirects));
+            }
+
+            debug!("↪️  {} {} -> {}", status.as_u16(), current, location);
+            current = location.to_string();
+        }
+    }
+
+    /// A GET for `url`, made conditional if the previous crawl stored
+    /// validators for it.
+    fn request(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.get(url);
+        match &self.previous {
+            Some(previous) => request.headers(previous.conditional_headers(url)),
+            None => request,
+        }
+    }
+
+    /// GET `url`, retrying transient failures with exponential backoff.
+    /// Returns the final response and how many attempts it took.
+    async fn fetch_with_retry(&self, url: &str) -> Result<(reqwest::Response, u32)> {
+        let policy = &self.config.retry;
+        let host = politeness::host_key(url);
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            let can_retry = attempt < policy.max_attempts;
+
+            let delay = match self.request(url).send().await {
+                Ok(response) if can_retry && policy.retries_status(response.status().as_u16()) => {
+                    let delay = match retry::retry_after(response.headers()) {
+                        // Waiting that long would stall the crawl, give up
+                        Some(delay) if delay > policy.max_backoff => {
+                            return Ok((response, attempt));
+                        }
+                        Some(delay) => delay,
+                        None => policy.backoff(attempt),
+                    };
+                    warn!(
+                        "🔁 {} returned {}, retrying in {}ms (attempt {}/{})",
+                        url,
+                        response.status(),
+                        delay.as_millis(),
+                        attempt + 1,
+                        policy.max_attempts
+                    );
+                    delay
+                }
+                Ok(response) => return Ok((response, attempt)),
+                Err(e) if can_retry && policy.retries_error(&e) => {
+                    let delay = policy.backoff(attempt);
+                    warn!(
+                        "🔁 {} failed ({}), retrying in {}ms (attempt {}/{})",
+                        url,
+                        e,
+                        delay.as_millis(),
+                        attempt + 1,
+                        policy.max_attempts
+                    );
+                    delay
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            // Push the whole host back, not just this URL, so other workers
+            // don't keep hitting a server that asked us to slow down
+            match &host {
+                Some(host) => {
+                    self.scheduler.back_off(host, delay).await;
+                    // The retry still keeps the host's usual spacing after that
+                    let spacing = self.politeness_delay(url, host).await;
+                    self.scheduler.wait_turn(host, spacing).await;
                 }
+                None => tokio::time::sleep(delay).await,
+            }
+        }
+    }
+
+    /// The spacing to keep between requests to this URL's host: the
+    /// largest of our configured delay, the robots.txt crawl-delay or
+    /// request-rate, and any per-host delay. A per-host delay can make the
+    /// crawl slower but never faster.
+    async fn politeness_delay(&self, url: &str, host: &str) -> Duration {
+        let mut delay = self.config.delay_between_requests;
+
+        // Fetches robots.txt if need be, so even the first request to a host
+        // waits as it asks
+        if self.config.respect_robots_txt {
+            if let Some(robots_delay) = self
+                .robots_checker
+                .get_crawl_delay(url, &self.config.user_agent)
+                .await
+                .filter(|robots_delay| *robots_delay > delay)
+            {
+                debug!(
+                    "Using {}ms robots.txt crawl-delay for {}",
+                    robots_delay.as_millis(),
+                    host
+                );
+                delay = robots_delay;
             }
         }
 
-        debug!("✨ Found {} valid links on {}", links.len(), base_url_str);
-        Ok((links, title))
+        if let Some(host_delay) = self.config.host_delays.get(host) {
+            delay = delay.max(*host_delay);
+        }
+
+        delay
     }
 
     async fn should_crawl_url(&self, url: &str) -> bool {
@@ -488,27 +1568,11 @@ impl Crawler {
     }
 
     fn normalize_url(&self, url: &Url) -> String {
-        let mut url = url.clone();
-
-        // Remove fragments (anchors)
-        url.set_fragment(None);
-
-        // Remove query parameters if desired
-        // url.set_query(None);
-
-        // Convert to string and remove trailing slash if present
-        let mut url_str = url.to_string();
-        if url_str.ends_with('/') {
-            url_str.pop();
-        }
-
-        url_str
+        self.config.normalization.normalize(url)
     }
 
-    async fn print_statistics(&self) {
-        let visited = self.visited.lock().await;
-        let graph = self.graph.lock().await;
-        let stats = self.stats.lock().await;
+    fn print_statistics(&self, graph: &HashMap<String, Vec<String>>, stats: &CrawlStats) {
+        let visited = &self.visited;
 
         let total_links = graph.values().map(|v| v.len()).sum::<usize>();
         let avg_links_per_page = if !graph.is_empty() {
@@ -524,15 +1588,35 @@ impl Crawler {
         info!("   Crawl duration: {:.2}s", stats.duration_secs);
         info!("   Successful requests: {}", stats.success_count);
         info!("   Failed requests: {}", stats.error_count);
+        if stats.robots_disallowed_count > 0 {
+            info!(
+                "   Disallowed by robots.txt: {}",
+                stats.robots_disallowed_count
+            );
+        }
+        if stats.not_modified_count > 0 {
+            info!(
+                "   Unchanged since last crawl: {}",
+                stats.not_modified_count
+            );
+        }
+        if stats.parsed_count > 0 {
+            info!(
+                "   HTML parsing: {:.2}ms average, {:.2}ms slowest ({} parser threads)",
+                stats.parse_time_secs * 1000.0 / stats.parsed_count as f64,
+                stats.max_parse_time_ms,
+                self.parser.threads()
+            );
+        }
 
         // Find page with most outgoing links
         if let Some((url, links)) = graph.iter().max_by_key(|(_, links)| links.len()) {
             info!("   Most linked page: {} with {} links", url, links.len());
         }
 
-        // Domain distribution
-        let domain_counts = visited
-            .iter()
+        // Domain distribution of the pages actually crawled
+        let domain_counts = graph
+            .keys()
             .filter_map(|url| self.extract_domain(url).ok())
             .fold(HashMap::new(), |mut acc, domain| {
                 *acc.entry(domain).or_insert(0) += 1;
@@ -554,13 +1638,38 @@ impl Clone for Crawler {
             visited: Arc::clone(&self.visited),
             graph: Arc::clone(&self.graph),
             pages: Arc::clone(&self.pages),
+            robots_disallowed: Arc::clone(&self.robots_disallowed),
             config: self.config.clone(),
             client: self.client.clone(),
-            limiter: Arc::clone(&self.limiter),
             robots_checker: self.robots_checker.clone(),
+            parser: self.parser.clone(),
             domain_counters: Arc::clone(&self.domain_counters),
             stats: Arc::clone(&self.stats),
+            counters: Arc::clone(&self.counters),
+            scheduler: self.scheduler.clone(),
+            seeds: Arc::clone(&self.seeds),
+            shutdown: self.shutdown.clone(),
+            events: self.events.clone(),
+            frontier: Arc::clone(&self.frontier),
+            frontier_ready: Arc::clone(&self.frontier_ready),
+            in_flight: Arc::clone(&self.in_flight),
+            parked: Arc::clone(&self.parked),
+            pending: Arc::clone(&self.pending),
+            queue_gate: Arc::clone(&self.queue_gate),
+            duplicates: Arc::clone(&self.duplicates),
+            previous: self.previous.clone(),
+            sinks: self.sinks.clone(),
         }
     }
 }
 
+// The `ETag` and `Last-Modified` validators of a response
+fn validators(headers: &HeaderMap) -> (Option<String>, Option<String>) {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|h| h.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    (header(ETAG), header(LAST_MODIFIED))
+}
@@ -0,0 +1,378 @@
+// src/diff.rs
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::storage::{StoredCrawlResult, StoredPage};
+
+/// What changed between two stored crawls. Every list is sorted by URL.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CrawlDiff {
+    pub pages_added: Vec<String>,
+    pub pages_removed: Vec<String>,
+    pub status_changes: Vec<Change<Option<u16>>>,
+    pub title_changes: Vec<Change<Option<String>>>,
+    pub depth_changes: Vec<Change<u32>>,
+    pub link_changes: Vec<LinkChange>,
+}
+
+/// A value of one page that differs between the two crawls.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change<T> {
+    pub url: String,
+    pub old: T,
+    pub new: T,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkChange {
+    pub url: String,
+    pub gained: Vec<String>,
+    pub lost: Vec<String>,
+}
+
+impl CrawlDiff {
+    pub fn is_empty(&self) -> bool {
+        self.pages_added.is_empty()
+            && self.pages_removed.is_empty()
+            && self.status_changes.is_empty()
+            && self.title_changes.is_empty()
+            && self.depth_changes.is_empty()
+            && self.link_changes.is_empty()
+    }
+}
+
+/// Compare an older crawl with a newer one. Pages are matched by URL; only
+/// pages present in both are checked for changes.
+pub fn diff_results(old: &StoredCrawlResult, new: &StoredCrawlResult) -> CrawlDiff {
+    let old_pages: BTreeMap<&str, &StoredPage> =
+        old.pages.iter().map(|p| (p.url.as_str(), p)).collect();
+    let new_pages: BTreeMap<&str, &StoredPage> =
+        new.pages.iter().map(|p| (p.url.as_str(), p)).collect();
+
+    let mut diff = CrawlDiff {
+        pages_added: new_pages
+            .keys()
+            .filter(|url| !old_pages.contains_key(*url))
+            .map(|url| url.to_string())
+            .collect(),
+        pages_removed: old_pages
+            .keys()
+            .filter(|url| !new_pages.contains_key(*url))
+            .map(|url| url.to_string())
+            .collect(),
+        ..CrawlDiff::default()
+    };
+
+    for (url, old_page) in &old_pages {
+        let Some(new_page) = new_pages.get(url) else {
+            continue;
+        };
+
+        if old_page.status_code != new_page.status_code {
+            diff.status_changes.push(Change {
+                url: url.to_string(),
+                old: old_page.status_code,
+                new: new_page.status_code,
+            });
+        }
+
+        if old_page.title != new_page.title {
+            diff.title_changes.push(Change {
+                url: url.to_string(),
+                old: old_page.title.clone(),
+                new: new_page.title.clone(),
+            });
+        }
+
+        if old_page.depth != new_page.depth {
+            diff.depth_changes.push(Change {
+                url: url.to_string(),
+                old: old_page.depth,
+                new: new_page.depth,
+            });
+        }
+
+        let old_links = links_of(old, old_page);
+        let new_links = links_of(new, new_page);
+        if old_links != new_links {
+            diff.link_changes.push(LinkChange {
+                url: url.to_string(),
+                gained: new_links.difference(&old_links).cloned().collect(),
+                lost: old_links.difference(&new_links).cloned().collect(),
+            });
+        }
+    }
+
+    diff
+}
+
+// The page's own links. The graph keys a redirected page by its final URL
+// (or its canonical, when collapsed), so it is only read for results written
+// before pages stored their links, which counted them without keeping them
+fn links_of(result: &StoredCrawlResult, page: &StoredPage) -> BTreeSet<String> {
+    let links_not_stored = page.links.is_empty() && page.links_count > 0;
+    match result.graph.get(&page.url) {
+        Some(links) if links_not_stored => links.iter().cloned().collect(),
+        _ => page.links.iter().cloned().collect(),
+    }
+}
+
+impl fmt::Display for CrawlDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+
+        if !self.pages_added.is_empty() {
+            writeln!(f, "Pages added ({}):", self.pages_added.len())?;
+            for url in &self.pages_added {
+                writeln!(f, "  + {}", url)?;
+            }
+        }
+
+        if !self.pages_removed.is_empty() {
+            writeln!(f, "Pages removed ({}):", self.pages_removed.len())?;
+            for url in &self.pages_removed {
+                writeln!(f, "  - {}", url)?;
+            }
+        }
+
+        if !self.status_changes.is_empty() {
+            writeln!(f, "Status changes ({}):", self.status_changes.len())?;
+            for change in &self.status_changes {
+                writeln!(
+                    f,
+                    "  {}: {} -> {}",
+                    change.url,
+                    or_none(change.old),
+                    or_none(change.new)
+                )?;
+            }
+        }
+
+        if !self.title_changes.is_empty() {
+            writeln!(f, "Title changes ({}):", self.title_changes.len())?;
+            for change in &self.title_changes {
+                writeln!(
+                    f,
+                    "  {}: {:?} -> {:?}",
+                    change.url,
+                    change.old.as_deref().unwrap_or(""),
+                    change.new.as_deref().unwrap_or("")
+                )?;
+            }
+        }
+
+        if !self.depth_changes.is_empty() {
+            writeln!(f, "Depth changes ({}):", self.depth_changes.len())?;
+            for change in &self.depth_changes {
+                writeln!(f, "  {}: {} -> {}", change.url, change.old, change.new)?;
+            }
+        }
+
+        if !self.link_changes.is_empty() {
+            writeln!(f, "Link changes ({}):", self.link_changes.len())?;
+            for change in &self.link_changes {
+                writeln!(f, "  {}", change.url)?;
+                for link in &change.gained {
+                    writeln!(f, "    + {}", link)?;
+                }
+                for link in &change.lost {
+                    writeln!(f, "    - {}", link)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn or_none(status: Option<u16>) -> String {
+    status.map_or_else(|| "none".to_string(), |s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn page(url: &str, status: u16, title: &str, depth: u32, links: &[&str]) -> serde_json::Value {
+        json!({
+            "url": url,
+            "title": title,
+            "depth": depth,
+            "status_code": status,
+            "content_type": "text/html",
+            "size_bytes": 10,
+            "links_count": links.len(),
+            "crawled_at": null,
+            "links": links,
+        })
+    }
+
+    fn result(pages: Vec<serde_json::Value>, graph: serde_json::Value) -> StoredCrawlResult {
+        serde_json::from_value(json!({
+            "pages_count": pages.len(),
+            "links_count": 0,
+            "crawl_duration_seconds": 0.0,
+            "success_count": pages.len(),
+            "error_count": 0,
+            "pages": pages,
+            "graph": graph,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn identical_crawls_have_no_changes() {
+        let crawl = || {
+            result(
+                vec![page("https://a.com/", 200, "Home", 0, &["https://a.com/x"])],
+                json!({}),
+            )
+        };
+        let diff = diff_results(&crawl(), &crawl());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "No changes\n");
+    }
+
+    #[test]
+    fn reports_added_and_removed_pages() {
+        let old = result(
+            vec![
+                page("https://a.com/", 200, "Home", 0, &[]),
+                page("https://a.com/gone", 200, "", 1, &[]),
+            ],
+            json!({}),
+        );
+        let new = result(
+            vec![
+                page("https://a.com/", 200, "Home", 0, &[]),
+                page("https://a.com/new", 200, "", 1, &[]),
+            ],
+            json!({}),
+        );
+
+        let diff = diff_results(&old, &new);
+        assert_eq!(diff.pages_added, vec!["https://a.com/new"]);
+        assert_eq!(diff.pages_removed, vec!["https://a.com/gone"]);
+        assert!(diff.status_changes.is_empty());
+        assert!(diff.link_changes.is_empty());
+    }
+
+    #[test]
+    fn reports_status_title_and_depth_changes() {
+        let old = result(vec![page("https://a.com/p", 200, "Old", 1, &[])], json!({}));
+        let new = result(vec![page("https://a.com/p", 404, "New", 2, &[])], json!({}));
+
+        let diff = diff_results(&old, &new);
+        assert_eq!(diff.status_changes.len(), 1);
+        assert_eq!(diff.status_changes[0].old, Some(200));
+        assert_eq!(diff.status_changes[0].new, Some(404));
+        assert_eq!(diff.title_changes[0].old.as_deref(), Some("Old"));
+        assert_eq!(diff.title_changes[0].new.as_deref(), Some("New"));
+        assert_eq!(
+            (diff.depth_changes[0].old, diff.depth_changes[0].new),
+            (1, 2)
+        );
+        assert_eq!(
+            diff.to_string(),
+            "Status changes (1):\n  https://a.com/p: 200 -> 404\n\
+             Title changes (1):\n  https://a.com/p: \"Old\" -> \"New\"\n\
+             Depth changes (1):\n  https://a.com/p: 1 -> 2\n"
+        );
+    }
+
+    #[test]
+    fn reports_links_gained_and_lost() {
+        let old = result(
+            vec![page(
+                "https://a.com/",
+                200,
+                "",
+                0,
+                &["https://a.com/x", "https://a.com/y"],
+            )],
+            json!({ "https://a.com/": ["https://a.com/x", "https://a.com/y"] }),
+        );
+        let new = result(
+            vec![page(
+                "https://a.com/",
+                200,
+                "",
+                0,
+                &["https://a.com/y", "https://a.com/z"],
+            )],
+            json!({ "https://a.com/": ["https://a.com/y", "https://a.com/z"] }),
+        );
+
+        let diff = diff_results(&old, &new);
+        assert_eq!(diff.link_changes.len(), 1);
+        assert_eq!(diff.link_changes[0].gained, vec!["https://a.com/z"]);
+        assert_eq!(diff.link_changes[0].lost, vec!["https://a.com/x"]);
+    }
+
+    // The graph holds only the redirect edge under the requested URL
+    #[test]
+    fn reports_link_changes_on_redirected_pages() {
+        let old = result(
+            vec![page("https://a.com/old", 200, "", 0, &["https://a.com/x"])],
+            json!({
+                "https://a.com/old": ["https://a.com/new"],
+                "https://a.com/new": ["https://a.com/x"],
+            }),
+        );
+        let new = result(
+            vec![page("https://a.com/old", 200, "", 0, &["https://a.com/y"])],
+            json!({
+                "https://a.com/old": ["https://a.com/new"],
+                "https://a.com/new": ["https://a.com/y"],
+            }),
+        );
+
+        let diff = diff_results(&old, &new);
+        assert_eq!(diff.link_changes.len(), 1);
+        assert_eq!(diff.link_changes[0].url, "https://a.com/old");
+        assert_eq!(diff.link_changes[0].gained, vec!["https://a.com/y"]);
+        assert_eq!(diff.link_changes[0].lost, vec!["https://a.com/x"]);
+    }
+
+    #[test]
+    fn page_without_links_is_not_read_from_the_graph() {
+        let crawl = || {
+            result(
+                vec![page("https://a.com/old", 200, "", 0, &[])],
+                json!({ "https://a.com/old": ["https://a.com/new"] }),
+            )
+        };
+        assert!(diff_results(&crawl(), &crawl()).is_empty());
+    }
+
+    // Older files counted links without storing them
+    #[test]
+    fn falls_back_to_the_graph_for_older_results() {
+        let mut old_page = page("https://a.com/", 200, "", 0, &[]);
+        old_page["links_count"] = json!(1);
+        old_page.as_object_mut().unwrap().remove("links");
+        let old = result(
+            vec![old_page],
+            json!({ "https://a.com/": ["https://a.com/x"] }),
+        );
+        let new = result(
+            vec![page(
+                "https://a.com/",
+                200,
+                "",
+                0,
+                &["https://a.com/x", "https://a.com/y"],
+            )],
+            json!({}),
+        );
+
+        let diff = diff_results(&old, &new);
+        assert_eq!(diff.link_changes.len(), 1);
+        assert_eq!(diff.link_changes[0].gained, vec!["https://a.com/y"]);
+        assert!(diff.link_changes[0].lost.is_empty());
+    }
+}
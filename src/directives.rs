@@ -0,0 +1,199 @@
+// src/directives.rs
+//! Page-level crawling directives from `<meta name="robots">` tags,
+//! `X-Robots-Tag` headers and `rel="nofollow"` links.
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::page::Page;
+use crate::robots_txt::agent_token;
+
+// Directives taking a value after a colon, which must not be mistaken for
+// the user agent prefix of an X-Robots-Tag value
+const VALUED_DIRECTIVES: &[&str] = &[
+    "unavailable_after",
+    "max-snippet",
+    "max-image-preview",
+    "max-video-preview",
+];
+
+/// What the crawler does about one kind of directive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirectivePolicy {
+    /// Record the directive on the page and act on it
+    #[default]
+    Obey,
+    /// Record the directive on the page, but crawl as if it weren't there
+    Record,
+    /// Neither record nor act on the directive
+    Ignore,
+}
+
+impl DirectivePolicy {
+    pub fn records(self) -> bool {
+        self != DirectivePolicy::Ignore
+    }
+
+    pub fn obeys(self) -> bool {
+        self == DirectivePolicy::Obey
+    }
+}
+
+/// How each directive the crawler can act on is handled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectivePolicies {
+    /// Page-wide `noindex`. Obeying leaves the page out of the results; its
+    /// links are still followed.
+    pub noindex: DirectivePolicy,
+    /// Page-wide `nofollow` (or `none`). Obeying follows none of the page's
+    /// links.
+    pub nofollow: DirectivePolicy,
+    /// `rel="nofollow"` on a link. Obeying doesn't follow that link.
+    pub link_nofollow: DirectivePolicy,
+}
+
+impl Default for DirectivePolicies {
+    fn default() -> Self {
+        Self {
+            // A crawler doesn't index, so by default noindex pages stay in
+            // the results
+            noindex: DirectivePolicy::Record,
+            nofollow: DirectivePolicy::Obey,
+            link_nofollow: DirectivePolicy::Obey,
+        }
+    }
+}
+
+impl DirectivePolicies {
+    /// Drop the directives whose policy is to ignore them.
+    pub fn filter(&self, mut directives: Vec<String>) -> Vec<String> {
+        directives.retain(|directive| match directive.as_str() {
+            "noindex" => self.noindex.records(),
+            "nofollow" => self.nofollow.records(),
+            _ => true,
+        });
+        directives
+    }
+
+    /// Whether a crawled page is kept out of the results.
+    pub fn excludes(&self, page: &Page) -> bool {
+        self.noindex.obeys() && page.has_directive("noindex")
+    }
+
+    /// The links of a crawled page the crawler should follow.
+    pub fn links_to_follow(&self, page: &Page) -> Vec<String> {
+        if self.nofollow.obeys() && page.has_directive("nofollow") {
+            return Vec::new();
+        }
+
+        let mut links = page.links.clone();
+        if self.link_nofollow.obeys() {
+            links.retain(|link| !page.nofollow_links.contains(link));
+        }
+        links
+    }
+}
+
+/// Split a directive list such as `noindex, nofollow` into lowercased
+/// directives, with `none` spelled out as `noindex` and `nofollow`.
+pub fn parse_directives(value: &str) -> Vec<String> {
+    let mut directives = Vec::new();
+    for directive in value.split(',') {
+        let directive = directive.trim().to_ascii_lowercase();
+        match directive.as_str() {
+            "" => {}
+            "none" => {
+                merge(
+                    &mut directives,
+                    ["noindex".to_string(), "nofollow".to_string()],
+                );
+            }
+            _ => merge(&mut directives, [directive]),
+        }
+    }
+    directives
+}
+
+/// Directives for `user_agent` from a page's `X-Robots-Tag` headers and
+/// meta tags: those addressed to every robot (no agent prefix, or
+/// `<meta name="robots">`) and those naming its product token.
+pub fn page_directives(
+    headers: &HeaderMap,
+    meta: &[(String, String)],
+    user_agent: &str,
+) -> Vec<String> {
+    let token = agent_token(user_agent);
+    let mut directives = Vec::new();
+
+    for value in headers
+        .get_all("x-robots-tag")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+    {
+        let list = match value.split_once(':') {
+            Some((prefix, rest)) if is_agent_prefix(prefix) => {
+                if agent_token(prefix) != token {
+                    continue;
+                }
+                rest
+            }
+            _ => value,
+        };
+        merge(&mut directives, parse_directives(list));
+    }
+
+    for (name, content) in meta {
+        if name == "robots" || *name == token {
+            merge(&mut directives, parse_directives(content));
+        }
+    }
+
+    directives
+}
+
+/// Source URL -> the linked URLs it marks nofollow, whether link by link or
+/// page-wide. Every such edge is also in the link graph. `source_url` gives
+/// the graph node a page's links hang off, which for a redirected page is
+/// not its requested URL.
+pub fn nofollow_edges(
+    pages: &[Page],
+    source_url: impl Fn(&Page) -> String,
+) -> HashMap<String, Vec<String>> {
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+
+    for page in pages {
+        let targets = if page.has_directive("nofollow") {
+            &page.links
+        } else {
+            &page.nofollow_links
+        };
+        if targets.is_empty() {
+            continue;
+        }
+
+        // Several requests can redirect to the same page
+        let flagged = edges.entry(source_url(page)).or_default();
+        for target in targets {
+            if !flagged.contains(target) {
+                flagged.push(target.clone());
+            }
+        }
+    }
+
+    edges
+}
+
+fn is_agent_prefix(prefix: &str) -> bool {
+    let prefix = prefix.trim().to_ascii_lowercase();
+    !prefix.is_empty() && !prefix.contains(',') && !VALUED_DIRECTIVES.contains(&prefix.as_str())
+}
+
+fn merge(directives: &mut Vec<String>, new: impl IntoIterator<Item = String>) {
+    for directive in new {
+        if !directives.contains(&directive) {
+            directives.push(directive);
+        }
+    }
+}
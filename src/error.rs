@@ -1,6 +1,7 @@
 // src/error.rs
 use thiserror::Error;
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum CrawlerError {
     #[error("HTTP request failed: {0}")]
@@ -18,6 +19,12 @@ pub enum CrawlerError {
     #[error("Robots.txt error: {0}")]
     RobotsError(String),
 
+    #[error("HTML parsing failed: {0}")]
+    ParseError(String),
+
+    #[error("Sitemap error: {0}")]
+    SitemapError(String),
+
     #[error("Config error: {0}")]
     ConfigError(String),
 
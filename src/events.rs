@@ -0,0 +1,62 @@
+// src/events.rs
+use futures::Stream;
+use serde::Serialize;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+use crate::crawler::CrawlStats;
+use crate::page::Page;
+
+/// Something that happened during a streaming crawl, in the order it happened.
+// Pages dominate the stream, so boxing them would only add an allocation
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Serialize)]
+pub enum CrawlEvent {
+    /// A page was fetched and processed
+    PageFetched(Page),
+    /// A link to a page not seen before was found and queued
+    LinkDiscovered {
+        from: String,
+        url: String,
+        depth: u32,
+    },
+    /// A queued page was not fetched
+    Skipped { url: String, reason: SkipReason },
+    /// Fetching a page failed, or (with no URL) the crawl itself did
+    Error { url: Option<String>, error: String },
+    /// The crawl is over; no more events follow
+    Finished(CrawlStats),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    MaxDepth,
+    MaxUrlsPerDomain,
+    MaxTotalUrls,
+    RobotsTxt,
+    /// Fetched, but left out of the results by its `noindex` directive
+    Noindex,
+}
+
+/// The stream returned by [`Crawler::crawl_stream`](crate::Crawler::crawl_stream).
+///
+/// Dropping it stops the crawl the same way a shutdown request does.
+pub struct CrawlEventStream {
+    rx: mpsc::Receiver<CrawlEvent>,
+}
+
+impl CrawlEventStream {
+    pub(crate) fn new(rx: mpsc::Receiver<CrawlEvent>) -> Self {
+        Self { rx }
+    }
+}
+
+impl Stream for CrawlEventStream {
+    type Item = CrawlEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<CrawlEvent>> {
+        self.rx.poll_recv(cx)
+    }
+}
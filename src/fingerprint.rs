@@ -0,0 +1,201 @@
+// src/fingerprint.rs
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use crate::page::Page;
+
+// Words per shingle fed into the SimHash
+const SHINGLE_WORDS: usize = 3;
+
+/// SHA-256 of the response body, hex-encoded. Equal hashes mean
+/// byte-identical pages.
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// 64-bit SimHash of a text over overlapping word shingles. Texts that
+/// differ only a little get hashes a small Hamming distance apart.
+pub fn simhash(text: &str) -> u64 {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    let mut weights = [0i64; 64];
+    let mut add = |hash: u64| {
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if hash & (1 << bit) != 0 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    };
+
+    if words.len() < SHINGLE_WORDS {
+        words.iter().for_each(|w| add(fnv1a(w.as_bytes())));
+    } else {
+        for shingle in words.windows(SHINGLE_WORDS) {
+            add(fnv1a(shingle.join(" ").as_bytes()));
+        }
+    }
+
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, &weight)| weight > 0)
+        .fold(0, |hash, (bit, _)| hash | (1 << bit))
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+// Stable across runs and platforms, unlike the std hasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Remembers the fingerprints of pages seen so far and finds the earlier
+/// page a new one duplicates.
+///
+/// Near-duplicate lookup splits the SimHash into `max_distance + 1` bands:
+/// two hashes within `max_distance` bits of each other must agree on at
+/// least one band, so only pages sharing a band are compared.
+#[derive(Debug)]
+pub struct DuplicateIndex {
+    max_distance: u32,
+    exact: HashMap<String, String>,
+    bands: HashMap<(u32, u64), Vec<(u64, String)>>,
+}
+
+impl DuplicateIndex {
+    pub fn new(max_distance: u32) -> Self {
+        Self {
+            max_distance: max_distance.min(63),
+            exact: HashMap::new(),
+            bands: HashMap::new(),
+        }
+    }
+
+    /// Record a page's fingerprints, returning the URL of an earlier page
+    /// with the same or nearly the same content. Pages without a SimHash (no
+    /// text) only match exact copies.
+    pub fn insert(
+        &mut self,
+        url: &str,
+        content_hash: &str,
+        simhash: Option<u64>,
+    ) -> Option<String> {
+        if let Some(original) = self.exact.get(content_hash) {
+            return Some(original.clone());
+        }
+        self.exact.insert(content_hash.to_string(), url.to_string());
+
+        let simhash = simhash?;
+
+        let mut original = None;
+        for key in self.band_keys(simhash) {
+            let bucket = self.bands.entry(key).or_default();
+            if original.is_none() {
+                original = bucket
+                    .iter()
+                    .find(|(other, _)| hamming_distance(simhash, *other) <= self.max_distance)
+                    .map(|(_, other_url)| other_url.clone());
+            }
+            bucket.push((simhash, url.to_string()));
+        }
+
+        original
+    }
+
+    fn band_keys(&self, simhash: u64) -> Vec<(u32, u64)> {
+        let bands = self.max_distance + 1;
+        let width = 64 / bands;
+
+        (0..bands)
+            .map(|band| {
+                let shift = band * width;
+                // The last band takes the leftover bits
+                let bits = if band == bands - 1 { 64 - shift } else { width };
+                let mask = if bits == 64 {
+                    u64::MAX
+                } else {
+                    (1 << bits) - 1
+                };
+                (band, (simhash >> shift) & mask)
+            })
+            .collect()
+    }
+}
+
+/// A group of crawled pages with the same or nearly the same content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCluster {
+    /// Every page in the cluster has the same content hash
+    pub exact: bool,
+    pub urls: Vec<String>,
+}
+
+/// Groups pages whose content is identical, or whose SimHashes are within
+/// `max_distance` bits of each other. Pages without fingerprints and pages
+/// with no duplicate are left out.
+pub fn duplicate_clusters(pages: &[Page], max_distance: u32) -> Vec<DuplicateCluster> {
+    let fingerprinted: Vec<(&Page, &str, Option<u64>)> = pages
+        .iter()
+        .filter_map(|p| Some((p, p.content_hash.as_deref()?, p.simhash)))
+        .collect();
+
+    // Union-find over the fingerprinted pages
+    let mut parent: Vec<usize> = (0..fingerprinted.len()).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut index = DuplicateIndex::new(max_distance);
+    let mut position: HashMap<&str, usize> = HashMap::new();
+    for (i, (page, hash, simhash)) in fingerprinted.iter().enumerate() {
+        position.insert(page.url.as_str(), i);
+        if let Some(original) = index.insert(&page.url, hash, *simhash) {
+            if let Some(&j) = position.get(original.as_str()) {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                parent[a] = b;
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..fingerprinted.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+
+    let mut clusters: Vec<DuplicateCluster> = groups
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|members| {
+            let first_hash = fingerprinted[members[0]].1;
+            let mut urls: Vec<String> = members
+                .iter()
+                .map(|&i| fingerprinted[i].0.url.clone())
+                .collect();
+            urls.sort();
+
+            DuplicateCluster {
+                exact: members.iter().all(|&i| fingerprinted[i].1 == first_hash),
+                urls,
+            }
+        })
+        .collect();
+
+    clusters.sort_by(|a, b| b.urls.len().cmp(&a.urls.len()).then(a.urls.cmp(&b.urls)));
+    clusters
+}
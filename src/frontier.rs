@@ -0,0 +1,807 @@
+// src/frontier.rs
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::Arc;
+
+use crate::error::{CrawlerError, Result};
+use crate::page::Page;
+use crate::politeness;
+
+/// Scores a page for [`BestFirstFrontier`]; higher scores are crawled first.
+pub type ScoreFn = Arc<dyn Fn(&Page) -> f64 + Send + Sync>;
+
+/// The queue of pages waiting to be crawled. The implementation decides the
+/// order in which they come out.
+pub trait Frontier: Send {
+    fn push(&mut self, page: Page);
+    fn pop(&mut self) -> Option<Page>;
+    fn len(&self) -> usize;
+
+    /// Every queued page, in no particular order. Used for checkpoints.
+    fn pages(&self) -> Vec<Page>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Built-in frontier orderings, selectable from the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrontierStrategy {
+    /// Shallowest pages first, in discovery order within a depth
+    #[default]
+    BreadthFirst,
+    /// Deepest pages first, most recently discovered first within a depth
+    DepthFirst,
+    /// Highest score first (see [`default_score`])
+    BestFirst,
+    /// One page per host in turn, breadth-first within a host
+    RoundRobin,
+}
+
+impl FrontierStrategy {
+    /// Create an empty frontier for this strategy. `score` is only used by
+    /// `BestFirst` and falls back to [`default_score`].
+    pub fn build(self, score: Option<ScoreFn>) -> Box<dyn Frontier> {
+        match self {
+            FrontierStrategy::BreadthFirst => Box::new(BreadthFirstFrontier::new()),
+            FrontierStrategy::DepthFirst => Box::new(DepthFirstFrontier::new()),
+            FrontierStrategy::BestFirst => Box::new(BestFirstFrontier::new(
+                score.unwrap_or_else(|| Arc::new(default_score)),
+            )),
+            FrontierStrategy::RoundRobin => Box::new(RoundRobinFrontier::new()),
+        }
+    }
+
+    /// How a [`SpillingFrontier`] over this strategy's frontier files its
+    /// spilled pages. `score` is as for [`FrontierStrategy::build`];
+    /// best-first pages are banded `score_band` wide.
+    pub fn spill_order(self, score: Option<ScoreFn>, score_band: f64) -> SpillOrder {
+        match self {
+            FrontierStrategy::BreadthFirst => SpillOrder::Depth {
+                deepest_first: false,
+            },
+            FrontierStrategy::DepthFirst => SpillOrder::Depth {
+                deepest_first: true,
+            },
+            FrontierStrategy::BestFirst => SpillOrder::Score {
+                score: score.unwrap_or_else(|| Arc::new(default_score)),
+                width: score_band,
+            },
+            FrontierStrategy::RoundRobin => SpillOrder::Discovery,
+        }
+    }
+}
+
+/// Prefers shallow pages, then short paths: section index pages tend to be
+/// the ones worth having when the crawl is capped. A sitemap `<priority>`
+/// (0.0 to 1.0) nudges a page up.
+pub fn default_score(page: &Page) -> f64 {
+    let segments = url::Url::parse(&page.url)
+        .ok()
+        .and_then(|url| {
+            url.path_segments()
+                .map(|s| s.filter(|s| !s.is_empty()).count())
+        })
+        .unwrap_or(0);
+
+    -(page.depth as f64) - 0.1 * segments as f64 + page.sitemap_priority.unwrap_or(0.0) as f64
+}
+
+// Heap entry ordered by its key only
+struct Prioritized<K> {
+    key: K,
+    page: Page,
+}
+
+impl<K: Ord> PartialEq for Prioritized<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord> Eq for Prioritized<K> {}
+
+impl<K: Ord> PartialOrd for Prioritized<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord> Ord for Prioritized<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// Strict breadth-first by `Page::depth`, even when pages arrive out of order
+/// from concurrent workers.
+#[derive(Default)]
+pub struct BreadthFirstFrontier {
+    heap: BinaryHeap<Prioritized<(Reverse<u32>, Reverse<u64>)>>,
+    seq: u64,
+}
+
+impl BreadthFirstFrontier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Frontier for BreadthFirstFrontier {
+    fn push(&mut self, page: Page) {
+        self.seq += 1;
+        let key = (Reverse(page.depth), Reverse(self.seq));
+        self.heap.push(Prioritized { key, page });
+    }
+
+    fn pop(&mut self) -> Option<Page> {
+        self.heap.pop().map(|entry| entry.page)
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn pages(&self) -> Vec<Page> {
+        self.heap.iter().map(|entry| entry.page.clone()).collect()
+    }
+}
+
+#[derive(Default)]
+pub struct DepthFirstFrontier {
+    heap: BinaryHeap<Prioritized<(u32, u64)>>,
+    seq: u64,
+}
+
+impl DepthFirstFrontier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Frontier for DepthFirstFrontier {
+    fn push(&mut self, page: Page) {
+        self.seq += 1;
+        let key = (page.depth, self.seq);
+        self.heap.push(Prioritized { key, page });
+    }
+
+    fn pop(&mut self) -> Option<Page> {
+        self.heap.pop().map(|entry| entry.page)
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn pages(&self) -> Vec<Page> {
+        self.heap.iter().map(|entry| entry.page.clone()).collect()
+    }
+}
+
+// f64 wrapper with a total order so scores can live in a heap
+#[derive(PartialEq)]
+struct Score(f64);
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Highest score first; ties go to the page discovered earliest.
+pub struct BestFirstFrontier {
+    heap: BinaryHeap<Prioritized<(Score, Reverse<u64>)>>,
+    seq: u64,
+    score: ScoreFn,
+}
+
+impl BestFirstFrontier {
+    pub fn new(score: ScoreFn) -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            seq: 0,
+            score,
+        }
+    }
+}
+
+impl Frontier for BestFirstFrontier {
+    fn push(&mut self, page: Page) {
+        self.seq += 1;
+        let key = (Score((self.score)(&page)), Reverse(self.seq));
+        self.heap.push(Prioritized { key, page });
+    }
+
+    fn pop(&mut self) -> Option<Page> {
+        self.heap.pop().map(|entry| entry.page)
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn pages(&self) -> Vec<Page> {
+        self.heap.iter().map(|entry| entry.page.clone()).collect()
+    }
+}
+
+/// Takes one page from each host in turn, so a single large site can't starve
+/// the others.
+#[derive(Default)]
+pub struct RoundRobinFrontier {
+    queues: HashMap<String, VecDeque<Page>>,
+    hosts: VecDeque<String>,
+    len: usize,
+}
+
+impl RoundRobinFrontier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Frontier for RoundRobinFrontier {
+    fn push(&mut self, page: Page) {
+        let host = politeness::host_key(&page.url).unwrap_or_default();
+        let queue = self.queues.entry(host.clone()).or_default();
+        if queue.is_empty() {
+            self.hosts.push_back(host);
+        }
+        queue.push_back(page);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<Page> {
+        let host = self.hosts.pop_front()?;
+        let queue = self.queues.get_mut(&host)?;
+        let page = queue.pop_front();
+
+        if queue.is_empty() {
+            self.queues.remove(&host);
+        } else {
+            self.hosts.push_back(host);
+        }
+
+        self.len -= 1;
+        page
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn pages(&self) -> Vec<Page> {
+        self.queues.values().flatten().cloned().collect()
+    }
+}
+
+/// How a [`SpillingFrontier`] files the pages it spills: each band goes to
+/// its own file, and the lowest band comes back first.
+#[derive(Clone)]
+pub enum SpillOrder {
+    /// By depth, shallowest first (deepest first with `deepest_first`)
+    Depth { deepest_first: bool },
+    /// By score, in bands `width` wide, highest band first
+    Score { score: ScoreFn, width: f64 },
+    /// All in one band, in discovery order
+    Discovery,
+}
+
+impl SpillOrder {
+    fn band(&self, page: &Page) -> i64 {
+        match self {
+            SpillOrder::Depth { deepest_first } if *deepest_first => -(page.depth as i64),
+            SpillOrder::Depth { .. } => page.depth as i64,
+            // The cast saturates, and puts a NaN score in band 0
+            SpillOrder::Score { score, width } => (-score(page) / width).floor() as i64,
+            SpillOrder::Discovery => 0,
+        }
+    }
+}
+
+// Distinguishes the spill directories of frontiers in the same process
+static SPILL_DIR_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/// Keeps at most `capacity` pages in a wrapped in-memory frontier and spills
+/// the overflow to append-only files on disk, one file per band of the
+/// [`SpillOrder`].
+///
+/// Spilled pages are read back in batches once memory runs half empty,
+/// best band first, and are then ordered by the wrapped frontier. While
+/// pages of some band are on disk, new pages that would not come before
+/// them are spilled too, so band order holds across the memory/disk
+/// boundary.
+///
+/// Within a band, only the pages in memory are in the wrapped frontier's
+/// order: a best-first crawl may take a page before a slightly better one
+/// of the same score band that is still on disk, and a round-robin crawl
+/// (one band in discovery order) rotates hosts among the pages in memory.
+pub struct SpillingFrontier {
+    memory: Box<dyn Frontier>,
+    capacity: usize,
+    // Our own subdirectory, removed on drop
+    dir: PathBuf,
+    order: SpillOrder,
+    spills: BTreeMap<i64, SpillFile>,
+    spilled: usize,
+    // How many pages of each band are in memory
+    memory_bands: BTreeMap<i64, usize>,
+}
+
+struct SpillFile {
+    path: PathBuf,
+    file: File,
+    // Where the next unread page starts
+    read_offset: u64,
+    len: usize,
+}
+
+impl SpillingFrontier {
+    /// Wrap `memory`, spilling to files in a fresh subdirectory of `dir`
+    /// (of the system temp dir if `None`). `order` should match the order
+    /// of `memory`.
+    pub fn new(
+        memory: Box<dyn Frontier>,
+        capacity: usize,
+        dir: Option<PathBuf>,
+        order: SpillOrder,
+    ) -> Result<Self> {
+        // Files left in `dir` by a killed run, or written by another
+        // crawler spilling there, must never be read back as our pages
+        let dir = dir.unwrap_or_else(std::env::temp_dir).join(format!(
+            "rust-crawler-frontier-{}-{}",
+            std::process::id(),
+            SPILL_DIR_SEQ.fetch_add(1, AtomicOrdering::Relaxed)
+        ));
+        create_spill_dir(&dir).map_err(|e| {
+            CrawlerError::StorageError(format!("Failed to create frontier spill directory: {}", e))
+        })?;
+
+        Ok(Self {
+            memory,
+            capacity: capacity.max(1),
+            dir,
+            order,
+            spills: BTreeMap::new(),
+            spilled: 0,
+            memory_bands: BTreeMap::new(),
+        })
+    }
+
+    // Whether the next spilled pages come before everything in memory, e.g.
+    // shallow pages spilled while memory was full of deeper ones
+    fn spilled_first(&self) -> bool {
+        match (self.spills.keys().next(), self.memory_bands.keys().next()) {
+            (Some(spilled), Some(memory)) => spilled < memory,
+            (spilled, _) => spilled.is_some(),
+        }
+    }
+
+    fn push_memory(&mut self, page: Page) {
+        *self.memory_bands.entry(self.order.band(&page)).or_insert(0) += 1;
+        self.memory.push(page);
+    }
+
+    // Whether a page of this band would be crawled after spilled pages
+    fn behind_spilled(&self, band: i64) -> bool {
+        self.spills
+            .keys()
+            .next()
+            .is_some_and(|&spilled| band >= spilled)
+    }
+
+    fn spill(&mut self, page: &Page, band: i64) -> io::Result<()> {
+        let mut line = serde_json::to_vec(page)?;
+        line.push(b'\n');
+
+        let spill = match self.spills.entry(band) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let path = self.dir.join(format!("band-{}.jsonl", band));
+                let file = OpenOptions::new()
+                    .create(true)
+                    .read(true)
+                    .append(true)
+                    .truncate(false)
+                    .open(&path)?;
+                entry.insert(SpillFile {
+                    path,
+                    file,
+                    read_offset: 0,
+                    len: 0,
+                })
+            }
+        };
+
+        // One write per page, so readers never see half a line
+        spill.file.write_all(&line)?;
+        spill.len += 1;
+        self.spilled += 1;
+        Ok(())
+    }
+
+    // Move up to `count` pages of the best spilled band back into memory
+    fn reload(&mut self, count: usize) {
+        let Some(mut entry) = self.spills.first_entry() else {
+            return;
+        };
+        let band = *entry.key();
+        let spill = entry.get_mut();
+
+        let read = read_spilled(&spill.file, spill.read_offset, Some(count));
+        let (pages, offset) = match read {
+            Ok(read) => read,
+            Err(e) => {
+                error!(
+                    "Lost {} spilled frontier pages in band {}: {}",
+                    spill.len, band, e
+                );
+                (Vec::new(), u64::MAX)
+            }
+        };
+
+        spill.read_offset = offset;
+        spill.len = spill.len.saturating_sub(pages.len());
+        self.spilled = self.spilled.saturating_sub(pages.len());
+
+        let exhausted = spill.len == 0 || offset == u64::MAX || pages.is_empty();
+        if exhausted {
+            let spill = entry.remove();
+            self.spilled = self.spilled.saturating_sub(spill.len);
+            let _ = fs::remove_file(&spill.path);
+        }
+
+        debug!(
+            "Reloaded {} spilled frontier pages in band {}",
+            pages.len(),
+            band
+        );
+        for page in pages {
+            self.push_memory(page);
+        }
+    }
+}
+
+// Creates `dir` empty. One already there was left by a dead process that
+// had our process id
+fn create_spill_dir(dir: &Path) -> io::Result<()> {
+    if let Some(parent) = dir.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::create_dir(dir) {
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            fs::remove_dir_all(dir)?;
+            fs::create_dir(dir)
+        }
+        result => result,
+    }
+}
+
+// Reads pages from `offset` on (at most `limit`), returning them and the
+// offset just past the last one read
+fn read_spilled(file: &File, offset: u64, limit: Option<usize>) -> io::Result<(Vec<Page>, u64)> {
+    let mut reader = BufReader::new(file);
+    reader.seek(SeekFrom::Start(offset))?;
+
+    let mut pages = Vec::new();
+    let mut offset = offset;
+    let mut line = String::new();
+    while limit.is_none_or(|limit| pages.len() < limit) {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            break;
+        }
+        offset += read as u64;
+        pages.push(serde_json::from_str(&line)?);
+    }
+
+    Ok((pages, offset))
+}
+
+impl Frontier for SpillingFrontier {
+    fn push(&mut self, page: Page) {
+        let band = self.order.band(&page);
+        if self.memory.len() < self.capacity && !self.behind_spilled(band) {
+            self.push_memory(page);
+            return;
+        }
+
+        if let Err(e) = self.spill(&page, band) {
+            // Better over the memory limit than losing the page
+            error!("Failed to spill frontier page {}: {}", page.url, e);
+            self.push_memory(page);
+        }
+    }
+
+    fn pop(&mut self) -> Option<Page> {
+        if self.spilled > 0 && self.memory.len() <= self.capacity / 2 {
+            self.reload(self.capacity - self.memory.len());
+        }
+        // Memory may be full of pages that go after spilled ones; bring at
+        // least one of those back so it's next
+        while self.spilled_first() {
+            self.reload(self.capacity.saturating_sub(self.memory.len()).max(1));
+        }
+
+        let page = self.memory.pop()?;
+        if let Entry::Occupied(mut count) = self.memory_bands.entry(self.order.band(&page)) {
+            *count.get_mut() -= 1;
+            if *count.get() == 0 {
+                count.remove();
+            }
+        }
+        Some(page)
+    }
+
+    fn len(&self) -> usize {
+        self.memory.len() + self.spilled
+    }
+
+    fn pages(&self) -> Vec<Page> {
+        let mut pages = self.memory.pages();
+        for spill in self.spills.values() {
+            match read_spilled(&spill.file, spill.read_offset, None) {
+                Ok((spilled, _)) => pages.extend(spilled),
+                Err(e) => error!("Failed to read spilled frontier pages: {}", e),
+            }
+        }
+        pages
+    }
+}
+
+impl Drop for SpillingFrontier {
+    fn drop(&mut self) {
+        for spill in self.spills.values() {
+            let _ = fs::remove_file(&spill.path);
+        }
+        let _ = fs::remove_dir(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::CrawlerBuilder;
+    use crate::config::CrawlerConfig;
+
+    fn page(url: &str, depth: u32) -> Page {
+        Page::new(format!("https://a.com/{}", url), depth)
+    }
+
+    fn spilling(strategy: FrontierStrategy, capacity: usize) -> SpillingFrontier {
+        SpillingFrontier::new(
+            strategy.build(None),
+            capacity,
+            None,
+            strategy.spill_order(None, 0.1),
+        )
+        .unwrap()
+    }
+
+    fn drain(frontier: &mut dyn Frontier) -> Vec<(u32, String)> {
+        std::iter::from_fn(|| frontier.pop())
+            .map(|page| (page.depth, page.url))
+            .collect()
+    }
+
+    #[test]
+    fn spills_past_capacity() {
+        let mut frontier = spilling(FrontierStrategy::BreadthFirst, 4);
+        for i in 0..10 {
+            frontier.push(page(&i.to_string(), 1));
+        }
+
+        assert_eq!(frontier.memory.len(), 4);
+        assert_eq!(frontier.spilled, 6);
+        assert_eq!(frontier.len(), 10);
+        assert_eq!(frontier.pages().len(), 10);
+    }
+
+    #[test]
+    fn reloads_breadth_first_in_depth_order() {
+        let mut frontier = spilling(FrontierStrategy::BreadthFirst, 3);
+        // Deep pages first, so shallow ones arrive while deep ones are
+        // already on disk
+        for i in 0..5 {
+            frontier.push(page(&format!("deep{}", i), 2));
+        }
+        for i in 0..5 {
+            frontier.push(page(&format!("mid{}", i), 1));
+        }
+        frontier.push(page("root", 0));
+
+        let popped = drain(&mut frontier);
+        assert_eq!(popped.len(), 11);
+        let depths: Vec<u32> = popped.iter().map(|(depth, _)| *depth).collect();
+        let mut sorted = depths.clone();
+        sorted.sort();
+        assert_eq!(depths, sorted);
+
+        // Discovery order holds within a depth
+        let mids: Vec<&str> = popped
+            .iter()
+            .filter(|(depth, _)| *depth == 1)
+            .map(|(_, url)| url.as_str())
+            .collect();
+        let expected: Vec<String> = (0..5).map(|i| format!("https://a.com/mid{}", i)).collect();
+        assert_eq!(mids, expected);
+        assert!(frontier.is_empty());
+    }
+
+    #[test]
+    fn reloads_depth_first_deepest_first() {
+        let mut frontier = spilling(FrontierStrategy::DepthFirst, 2);
+        for depth in 0..4 {
+            for i in 0..3 {
+                frontier.push(page(&format!("{}-{}", depth, i), depth));
+            }
+        }
+
+        let depths: Vec<u32> = drain(&mut frontier).iter().map(|(d, _)| *d).collect();
+        assert_eq!(depths.len(), 12);
+        let mut sorted = depths.clone();
+        sorted.sort_by(|a, b| b.cmp(a));
+        assert_eq!(depths, sorted);
+    }
+
+    #[test]
+    fn pushes_between_pops_keep_depth_order() {
+        let mut frontier = spilling(FrontierStrategy::BreadthFirst, 2);
+        for i in 0..4 {
+            frontier.push(page(&format!("a{}", i), 1));
+        }
+
+        let mut depths = Vec::new();
+        while let Some(popped) = frontier.pop() {
+            depths.push(popped.depth);
+            // Each crawled page discovers a deeper one
+            if popped.depth < 3 {
+                frontier.push(page(
+                    &format!("{}-next", popped.url.len()),
+                    popped.depth + 1,
+                ));
+            }
+        }
+
+        let mut sorted = depths.clone();
+        sorted.sort();
+        assert_eq!(depths, sorted);
+    }
+
+    #[test]
+    fn removes_spill_files_on_drop() {
+        let mut frontier = spilling(FrontierStrategy::BreadthFirst, 1);
+        for i in 0..3 {
+            frontier.push(page(&i.to_string(), 1));
+        }
+        let dir = frontier.dir.clone();
+        assert!(dir.join("band-1.jsonl").exists());
+
+        drop(frontier);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn ignores_files_already_in_the_spill_dir() {
+        let base = std::env::temp_dir().join(format!("frontier_stale_{}", std::process::id()));
+        fs::create_dir_all(&base).unwrap();
+        let stale = serde_json::to_string(&page("stale", 1)).unwrap() + "\n";
+        fs::write(base.join("band-1.jsonl"), stale.repeat(5)).unwrap();
+
+        let strategy = FrontierStrategy::BreadthFirst;
+        let mut frontier = SpillingFrontier::new(
+            strategy.build(None),
+            1,
+            Some(base.clone()),
+            strategy.spill_order(None, 0.1),
+        )
+        .unwrap();
+        for i in 0..3 {
+            frontier.push(page(&i.to_string(), 1));
+        }
+        let urls: Vec<String> = drain(&mut frontier)
+            .into_iter()
+            .map(|(_, url)| url)
+            .collect();
+        assert_eq!(
+            urls,
+            ["https://a.com/0", "https://a.com/1", "https://a.com/2"]
+        );
+
+        // The caller's directory and what was in it are left alone
+        drop(frontier);
+        assert!(base.join("band-1.jsonl").exists());
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    // Scores arrive worst first, so the best pages are spilled while
+    // memory is full of worse ones
+    #[test]
+    fn reloads_best_first_in_score_order() {
+        let score: ScoreFn = Arc::new(|page: &Page| -(page.url.len() as f64));
+        let strategy = FrontierStrategy::BestFirst;
+        let mut frontier = SpillingFrontier::new(
+            strategy.build(Some(Arc::clone(&score))),
+            3,
+            None,
+            strategy.spill_order(Some(Arc::clone(&score)), 1.0),
+        )
+        .unwrap();
+        for len in (1..=12).rev() {
+            frontier.push(page(&"x".repeat(len), 1));
+            frontier.push(page(&"y".repeat(len), 1));
+        }
+        assert!(frontier.spilled > 0);
+
+        // A band is one point wide, so every score has its own
+        let scores: Vec<f64> = std::iter::from_fn(|| frontier.pop())
+            .map(|page| score(&page))
+            .collect();
+        assert_eq!(scores.len(), 24);
+        assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]));
+    }
+
+    #[test]
+    fn best_first_bands_are_as_wide_as_configured() {
+        let order = FrontierStrategy::BestFirst
+            .spill_order(Some(Arc::new(|page: &Page| page.depth as f64 / 10.0)), 0.25);
+        let bands: Vec<i64> = (0..6).map(|depth| order.band(&page("", depth))).collect();
+        // Scores 0.0 to 0.5, best (lowest band) last
+        assert_eq!(bands, [0, -1, -1, -2, -2, -2]);
+    }
+
+    #[test]
+    fn round_robin_spills_without_losing_pages() {
+        let mut frontier = spilling(FrontierStrategy::RoundRobin, 4);
+        for i in 0..10 {
+            frontier.push(Page::new(format!("https://h{}.com/a", i % 3), 1));
+        }
+
+        assert_eq!(frontier.spilled, 6);
+        assert_eq!(drain(&mut frontier).len(), 10);
+        assert!(frontier.is_empty());
+    }
+
+    #[test]
+    fn spilling_works_with_every_strategy() {
+        for strategy in [
+            FrontierStrategy::BreadthFirst,
+            FrontierStrategy::DepthFirst,
+            FrontierStrategy::BestFirst,
+            FrontierStrategy::RoundRobin,
+        ] {
+            let config = CrawlerConfig {
+                frontier_strategy: strategy,
+                frontier_memory_limit: Some(10),
+                ..CrawlerConfig::default()
+            };
+            assert!(CrawlerBuilder::from_config(config).build().is_ok());
+        }
+
+        let config = CrawlerConfig {
+            frontier_memory_limit: Some(10),
+            frontier_score_band: 0.0,
+            ..CrawlerConfig::default()
+        };
+        assert!(CrawlerBuilder::from_config(config).build().is_err());
+    }
+}
@@ -0,0 +1,195 @@
+// src/incremental.rs
+use reqwest::header::{HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH};
+use std::collections::HashMap;
+use std::path::Path;
+use url::Url;
+
+use crate::error::Result;
+use crate::storage::{self, StoredCrawlResult, StoredPage};
+
+/// The pages of an earlier crawl, used to revalidate pages with conditional
+/// requests instead of downloading them again.
+#[derive(Debug, Default)]
+pub struct PreviousCrawl {
+    pages: Vec<StoredPage>,
+    // Index into `pages` by both the requested URL and the URL the content
+    // was actually served from, in parsed form so `https://a.com` and
+    // `https://a.com/` meet
+    by_url: HashMap<String, usize>,
+}
+
+impl PreviousCrawl {
+    pub fn new(result: StoredCrawlResult) -> Self {
+        let mut by_url = HashMap::new();
+        for (i, page) in result.pages.iter().enumerate() {
+            by_url.entry(url_key(&page.url)).or_insert(i);
+        }
+        // Where the content came from wins over a request that redirected
+        // there
+        for (i, page) in result.pages.iter().enumerate() {
+            if let Some(final_url) = &page.final_url {
+                by_url.insert(url_key(final_url), i);
+            }
+        }
+
+        Self {
+            pages: result.pages,
+            by_url,
+        }
+    }
+
+    /// Load a results file written by [`storage::save_results`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::new(storage::load_results(path)?))
+    }
+
+    /// The stored page whose content was served from `url`, or else the one
+    /// requested as `url`.
+    pub fn get(&self, url: &str) -> Option<&StoredPage> {
+        self.by_url.get(&url_key(url)).map(|&i| &self.pages[i])
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// `If-None-Match` / `If-Modified-Since` headers for the validators
+    /// stored for `url`; empty if there are none.
+    pub fn conditional_headers(&self, url: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let Some(page) = self.get(url) else {
+            return headers;
+        };
+
+        if let Some(etag) = page
+            .etag
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = page
+            .last_modified
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_MODIFIED_SINCE, last_modified);
+        }
+
+        headers
+    }
+}
+
+fn url_key(url: &str) -> String {
+    Url::parse(url)
+        .map(|u| u.to_string())
+        .unwrap_or_else(|_| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn page(url: &str, final_url: Option<&str>, etag: &str) -> serde_json::Value {
+        json!({
+            "url": url,
+            "final_url": final_url,
+            "etag": etag,
+            "last_modified": "Mon, 01 Jan 2024 00:00:00 GMT",
+            "title": null,
+            "depth": 0,
+            "status_code": 200,
+            "content_type": "text/html",
+            "size_bytes": 10,
+            "links_count": 0,
+            "crawled_at": null,
+        })
+    }
+
+    fn previous(pages: Vec<serde_json::Value>) -> PreviousCrawl {
+        let result = json!({
+            "pages_count": pages.len(),
+            "links_count": 0,
+            "crawl_duration_seconds": 0.0,
+            "success_count": pages.len(),
+            "error_count": 0,
+            "pages": pages,
+            "graph": {},
+        });
+        PreviousCrawl::new(serde_json::from_value(result).unwrap())
+    }
+
+    fn etag(previous: &PreviousCrawl, url: &str) -> Option<String> {
+        previous.get(url).and_then(|page| page.etag.clone())
+    }
+
+    #[test]
+    fn finds_pages_by_requested_url() {
+        let previous = previous(vec![page("https://a.com/p", None, "\"1\"")]);
+        assert_eq!(etag(&previous, "https://a.com/p").as_deref(), Some("\"1\""));
+        assert!(previous.get("https://a.com/q").is_none());
+    }
+
+    #[test]
+    fn finds_redirected_pages_by_either_url() {
+        let previous = previous(vec![page(
+            "https://a.com/old",
+            Some("https://a.com/new"),
+            "\"1\"",
+        )]);
+        assert_eq!(
+            etag(&previous, "https://a.com/old").as_deref(),
+            Some("\"1\"")
+        );
+        assert_eq!(
+            etag(&previous, "https://a.com/new").as_deref(),
+            Some("\"1\"")
+        );
+    }
+
+    // The validators belong to where the content came from, so a page
+    // fetched there directly doesn't lose out to a request redirected to it
+    #[test]
+    fn final_url_wins_over_requested_url() {
+        let previous = previous(vec![
+            page("https://a.com/new", None, "\"direct\""),
+            page("https://a.com/old", Some("https://a.com/new"), "\"moved\""),
+        ]);
+        assert_eq!(
+            etag(&previous, "https://a.com/new").as_deref(),
+            Some("\"moved\"")
+        );
+        assert_eq!(
+            etag(&previous, "https://a.com/old").as_deref(),
+            Some("\"moved\"")
+        );
+    }
+
+    #[test]
+    fn urls_meet_in_parsed_form() {
+        let previous = previous(vec![page("https://a.com", None, "\"1\"")]);
+        assert_eq!(etag(&previous, "https://a.com/").as_deref(), Some("\"1\""));
+        assert_eq!(etag(&previous, "https://A.com").as_deref(), Some("\"1\""));
+    }
+
+    #[test]
+    fn conditional_headers_carry_stored_validators() {
+        let previous = previous(vec![page(
+            "https://a.com/old",
+            Some("https://a.com/new"),
+            "\"1\"",
+        )]);
+
+        let headers = previous.conditional_headers("https://a.com/new");
+        assert_eq!(headers[IF_NONE_MATCH], "\"1\"");
+        assert_eq!(headers[IF_MODIFIED_SINCE], "Mon, 01 Jan 2024 00:00:00 GMT");
+        assert!(previous
+            .conditional_headers("https://a.com/other")
+            .is_empty());
+    }
+}
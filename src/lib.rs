@@ -0,0 +1,66 @@
+// src/lib.rs
+//! A concurrent web crawler.
+//!
+//! The easiest way in is [`CrawlerBuilder`], which wraps [`CrawlerConfig`] and
+//! lets callers plug in their own HTTP client, robots.txt checker and output
+//! sinks:
+//!
+//! ```no_run
+//! use rust_crawler::{CrawlerBuilder, JsonFileSink};
+//!
+//! # async fn run() -> rust_crawler::Result<()> {
+//! let crawler = CrawlerBuilder::new()
+//!     .max_depth(3)
+//!     .concurrent_tasks(16)
+//!     .sink(JsonFileSink::new("results.json"))
+//!     .build()?;
+//!
+//! let result = crawler.crawl("https://example.com").await?;
+//! println!("crawled {} pages", result.pages.len());
+//! # Ok(())
+//! # }
+//! ```
+pub mod builder;
+pub mod canonical;
+pub mod checkpoint;
+pub mod config;
+pub mod crawler;
+pub mod diff;
+pub mod directives;
+pub mod error;
+pub mod events;
+pub mod fingerprint;
+pub mod frontier;
+pub mod incremental;
+pub mod normalize;
+pub mod page;
+pub mod parser;
+pub mod politeness;
+pub mod retry;
+pub mod robots;
+pub mod robots_txt;
+pub mod seed;
+pub mod sink;
+pub mod sitemap;
+pub mod storage;
+pub mod visited;
+pub mod visualization;
+
+pub use builder::CrawlerBuilder;
+pub use checkpoint::Checkpoint;
+pub use config::CrawlerConfig;
+pub use crawler::{CrawlResult, CrawlStats, Crawler, ShutdownHandle};
+pub use directives::{DirectivePolicies, DirectivePolicy};
+pub use error::{CrawlerError, Result};
+pub use events::{CrawlEvent, CrawlEventStream, SkipReason};
+pub use frontier::{Frontier, FrontierStrategy};
+pub use incremental::PreviousCrawl;
+pub use normalize::NormalizationRules;
+pub use page::Page;
+pub use parser::ParsePool;
+pub use robots::{RobotsChecker, RobotsConfig, RobotsStatus};
+pub use robots_txt::{RequestRate, RobotsTxt};
+pub use seed::Seed;
+pub use sink::{DotFileSink, HtmlFileSink, JsonFileSink, OutputSink};
+pub use visited::VisitedStore;
+pub use visualization::GraphVisualizer;
@@ -1,28 +1,33 @@
 // src/main.rs
-mod config;
-mod crawler;
-mod error;
-mod page;
-mod robots;
-mod storage;
-mod visualization;
-
-use clap::Parser;
-use config::CrawlerConfig;
-use crawler::Crawler;
-use error::Result;
-use log::{info, LevelFilter};
+use clap::{Parser, Subcommand, ValueEnum};
+use log::{info, warn, LevelFilter};
+use reqwest::StatusCode;
+use rust_crawler::config::{self, CrawlerConfig};
+use rust_crawler::robots_txt::{self, RobotsTxt, Verdict};
+use rust_crawler::visited::VisitedStoreConfig;
+use rust_crawler::{checkpoint, diff, seed, storage, CrawlerError};
+use rust_crawler::{CrawlerBuilder, DotFileSink, HtmlFileSink, JsonFileSink, Result, Seed};
+use rust_crawler::{RobotsChecker, RobotsStatus};
+use std::sync::Arc;
 
 #[derive(Parser)]
 #[command(
     author,
     version,
-    about = "A high-performance web crawler written in Rust"
+    about = "A high-performance web crawler written in Rust",
+    args_conflicts_with_subcommands = true
 )]
 struct Args {
-    /// URL to start crawling from
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// URL to start crawling from (may be given several times)
     #[arg(short, long)]
-    url: String,
+    url: Vec<String>,
+
+    /// File with one seed URL per line, optionally followed by `depth=N` and `tag=NAME`
+    #[arg(long)]
+    seeds_file: Option<String>,
 
     /// Maximum depth to crawl
     #[arg(short, long, default_value = "2")]
@@ -32,6 +37,10 @@ struct Args {
     #[arg(short = 'n', long, default_value = "8")] // Changed from 'c' to 'n'
     concurrency: usize,
 
+    /// Threads parsing HTML (defaults to one per CPU)
+    #[arg(long)]
+    parse_threads: Option<usize>,
+
     /// Path to configuration file
     #[arg(short = 'f', long)] // Changed from 'c' to 'f'
     config_file: Option<String>,
@@ -40,6 +49,18 @@ struct Args {
     #[arg(long, default_value = "true")]
     respect_robots: bool,
 
+    /// Keep fetched robots.txt files in this database file between runs
+    #[arg(long)]
+    robots_cache: Option<String>,
+
+    /// Also queue URLs found in each seed site's sitemaps
+    #[arg(long)]
+    sitemaps: bool,
+
+    /// Merge URL variants declaring the same rel=canonical into one graph node
+    #[arg(long)]
+    collapse_canonical: bool,
+
     /// Delay between requests in milliseconds
     #[arg(long, default_value = "100")]
     delay: u64,
@@ -60,6 +81,23 @@ struct Args {
     #[arg(long)]
     html_output: Option<String>,
 
+    /// Periodically write a checkpoint of the crawl state to this file
+    #[arg(long)]
+    checkpoint: Option<String>,
+
+    /// Seconds between checkpoints
+    #[arg(long, default_value = "60")]
+    checkpoint_interval: u64,
+
+    /// Resume an interrupted crawl from a checkpoint file
+    #[arg(long)]
+    resume: Option<String>,
+
+    /// Revalidate pages from an earlier results file with conditional
+    /// requests instead of downloading them again
+    #[arg(long)]
+    incremental: Option<String>,
+
     /// Generate example configuration file
     #[arg(long)]
     generate_config: Option<String>,
@@ -75,6 +113,58 @@ struct Args {
     #[arg(long, default_value = "10")]
     max_links_per_node: usize,
 }
+#[derive(Subcommand)]
+enum Command {
+    /// Compare two results files written with --output
+    Diff {
+        /// The earlier crawl
+        old: String,
+
+        /// The later crawl
+        new: String,
+
+        #[arg(long, value_enum, default_value = "text")]
+        format: DiffFormat,
+    },
+
+    /// Test robots.txt rules without crawling
+    Robots {
+        #[command(subcommand)]
+        command: RobotsCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum RobotsCommand {
+    /// Show whether each URL may be crawled, and the rule deciding it
+    Check {
+        /// URLs to check (or paths, when --robots is given)
+        #[arg(required = true)]
+        urls: Vec<String>,
+
+        /// robots.txt file or URL to check against, instead of fetching
+        /// each URL's own
+        #[arg(long)]
+        robots: Option<String>,
+
+        /// User agent to check for (defaults to the crawler's)
+        #[arg(long)]
+        user_agent: Option<String>,
+    },
+
+    /// Report syntax problems in a robots.txt file or URL
+    Lint {
+        /// robots.txt file or URL
+        robots: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DiffFormat {
+    Text,
+    Json,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse command line arguments
@@ -93,6 +183,12 @@ async fn main() -> Result<()> {
         .format_timestamp_millis()
         .init();
 
+    match args.command {
+        Some(Command::Diff { old, new, format }) => return run_diff(&old, &new, format),
+        Some(Command::Robots { command }) => return run_robots(command).await,
+        None => {}
+    }
+
     // Check if we need to generate a config file
     if let Some(config_path) = args.generate_config {
         config::create_example_config(&config_path)?;
@@ -110,16 +206,47 @@ async fn main() -> Result<()> {
     // Override config with command line arguments
     config.max_depth = args.depth;
     config.concurrent_tasks = args.concurrency;
+    if args.parse_threads.is_some() {
+        config.parse_threads = args.parse_threads;
+    }
     config.respect_robots_txt = args.respect_robots;
     config.delay_between_requests = std::time::Duration::from_millis(args.delay);
 
+    if let Some(robots_cache) = args.robots_cache {
+        config.robots.cache_path = Some(robots_cache);
+    }
+
+    if args.sitemaps {
+        config.use_sitemaps = true;
+    }
+
+    if args.collapse_canonical {
+        config.collapse_canonical_duplicates = true;
+    }
+
+    if let Some(previous_results) = args.incremental {
+        config.previous_results = Some(previous_results);
+    }
+
     if let Some(user_agent) = args.user_agent {
         config.user_agent = user_agent;
     }
 
+    // A resumed crawl carries on with the URLs its visited store already has
+    if args.resume.is_some() {
+        if let VisitedStoreConfig::Disk { reuse, .. } = &mut config.visited_store {
+            *reuse = true;
+        }
+    }
+
+    // Keep checkpointing into the file we resume from unless told otherwise
+    if let Some(checkpoint_path) = args.checkpoint.as_ref().or(args.resume.as_ref()) {
+        config.checkpoint_path = Some(checkpoint_path.clone());
+        config.checkpoint_interval = std::time::Duration::from_secs(args.checkpoint_interval);
+    }
+
     // Display configuration
     info!("Starting crawler with configuration:");
-    info!("   URL: {}", args.url);
     info!("   Max depth: {}", config.max_depth);
     info!("   Concurrent tasks: {}", config.concurrent_tasks);
     info!(
@@ -128,31 +255,253 @@ async fn main() -> Result<()> {
     );
     info!("   Respect robots.txt: {}", config.respect_robots_txt);
 
-    // Initialize the crawler
-    let crawler = Crawler::new(config)?;
+    // Initialize the crawler, registering a sink for each requested output
+    let mut builder = CrawlerBuilder::from_config(config);
+
+    if let Some(output_file) = &args.output {
+        builder = builder.sink(JsonFileSink::new(output_file));
+    }
+
+    if let Some(dot_path) = &args.dot_output {
+        builder = builder.sink(DotFileSink::new(dot_path));
+    }
+
+    if let Some(html_path) = &args.html_output {
+        builder = builder.sink(
+            HtmlFileSink::new(html_path)
+                .with_max_nodes(args.max_viz_nodes)
+                .with_max_links_per_node(args.max_links_per_node),
+        );
+    }
 
-    // Start crawling
-    let result = crawler.crawl(&args.url).await?;
+    let crawler = builder.build()?;
 
-    info!("Crawl completed: {} pages processed", result.pages.len());
+    // Stop gracefully on Ctrl-C / SIGTERM; a second signal exits at once
+    let shutdown = crawler.shutdown_handle();
+    tokio::spawn(async move {
+        wait_for_shutdown_signal().await;
+        warn!("Shutdown signal received, finishing in-flight requests (signal again to abort)");
+        shutdown.shutdown();
+
+        wait_for_shutdown_signal().await;
+        std::process::exit(130);
+    });
+
+    // Start crawling (or pick up where a checkpoint left off); sinks are
+    // written before this returns
+    let result = if let Some(checkpoint_path) = &args.resume {
+        let checkpoint = checkpoint::load_checkpoint(checkpoint_path)?;
+        crawler.resume(checkpoint).await?
+    } else {
+        let mut seeds: Vec<Seed> = args.url.iter().map(Seed::new).collect();
+        if let Some(seeds_file) = &args.seeds_file {
+            seeds.extend(seed::load_seeds_from_file(seeds_file)?);
+        }
+        crawler.crawl_seeds(seeds).await?
+    };
+
+    if result.partial {
+        info!(
+            "Crawl interrupted: {} pages processed, results are partial",
+            result.pages.len()
+        );
+    } else {
+        info!("Crawl completed: {} pages processed", result.pages.len());
+    }
 
-    // Save results if output file specified
     if let Some(output_file) = args.output {
-        storage::save_results(&result, &output_file)?;
         info!("Results saved to: {}", output_file);
     }
 
-    // Then replace the HTML visualization section in main function with:
+    if let Some(dot_path) = args.dot_output {
+        info!("Graph exported to DOT: {}", dot_path);
+    }
+
     if let Some(html_path) = args.html_output {
-        let mut visualizer = visualization::GraphVisualizer::new();
-        visualizer.build_from_crawler_graph(&result.graph);
-        visualizer.export_html_optimized(
-            &html_path,
-            args.max_viz_nodes,
-            args.max_links_per_node,
-        )?;
         info!("Interactive visualization exported to HTML: {}", html_path);
     }
     Ok(())
 }
 
+fn run_diff(old: &str, new: &str, format: DiffFormat) -> Result<()> {
+    let old = storage::load_results(old)?;
+    let new = storage::load_results(new)?;
+    let changes = diff::diff_results(&old, &new);
+
+    match format {
+        DiffFormat::Text => print!("{}", changes),
+        DiffFormat::Json => {
+            let json = serde_json::to_string_pretty(&changes).map_err(|e| {
+                CrawlerError::StorageError(format!("Failed to serialize diff: {}", e))
+            })?;
+            println!("{}", json);
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_robots(command: RobotsCommand) -> Result<()> {
+    let mut config = CrawlerConfig::default();
+
+    match command {
+        RobotsCommand::Check {
+            urls,
+            robots,
+            user_agent,
+        } => {
+            if let Some(user_agent) = user_agent {
+                config.user_agent = user_agent;
+            }
+            let checker = RobotsChecker::from_config(&config)?;
+
+            // One robots.txt for every URL, or each URL's site's own
+            let shared = match robots.as_deref() {
+                Some(source) if is_http_url(source) => {
+                    let (code, content) = fetch_robots_file(&checker, source).await?;
+                    let status = robots_status(code);
+                    let robots = match status {
+                        RobotsStatus::Fetched => RobotsTxt::parse(&content),
+                        RobotsStatus::Unavailable => RobotsTxt::default(),
+                        RobotsStatus::Unreachable => RobotsTxt::disallow_all(),
+                    };
+                    Some((Arc::new(robots), Some(status)))
+                }
+                Some(source) => Some((
+                    Arc::new(RobotsTxt::parse(&read_robots_file(source)?)),
+                    Some(RobotsStatus::Fetched),
+                )),
+                None => None,
+            };
+
+            for url in &urls {
+                let (robots, status) = match &shared {
+                    Some(shared) => shared.clone(),
+                    None if is_http_url(url) => {
+                        (checker.robots_txt(url).await?, checker.status(url).await)
+                    }
+                    None => {
+                        println!("{}: not a URL (give --robots to check a path)", url);
+                        continue;
+                    }
+                };
+
+                let verdict = robots.check(&path_and_query(url), &config.user_agent);
+                println!("{}: {}", url, describe_verdict(&verdict, status));
+            }
+        }
+        RobotsCommand::Lint { robots } => {
+            let content = if is_http_url(&robots) {
+                let checker = RobotsChecker::from_config(&config)?;
+                let (code, content) = fetch_robots_file(&checker, &robots).await?;
+                if !code.is_success() {
+                    return Err(CrawlerError::RobotsError(format!(
+                        "{} answered {}",
+                        robots, code
+                    )));
+                }
+                content
+            } else {
+                read_robots_file(&robots)?
+            };
+
+            let issues = robots_txt::lint(&content);
+            if issues.is_empty() {
+                println!("{}: no problems found", robots);
+                return Ok(());
+            }
+
+            for issue in &issues {
+                println!("{}: {}", robots, issue);
+            }
+            return Err(CrawlerError::RobotsError(format!(
+                "{} problem(s) found in {}",
+                issues.len(),
+                robots
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn describe_verdict(verdict: &Verdict<'_>, status: Option<RobotsStatus>) -> String {
+    let outcome = if verdict.allowed {
+        "allowed"
+    } else {
+        "disallowed"
+    };
+
+    match (status, verdict.rule) {
+        (Some(RobotsStatus::Unreachable), _) => {
+            format!("{} (robots.txt could not be fetched)", outcome)
+        }
+        (Some(RobotsStatus::Unavailable), _) => format!("{} (no robots.txt)", outcome),
+        (_, Some(rule)) => format!("{} by \"{}\" (line {})", outcome, rule, rule.line),
+        (_, None) => format!("{} (no rule matches)", outcome),
+    }
+}
+
+fn is_http_url(source: &str) -> bool {
+    url::Url::parse(source).is_ok_and(|url| url.scheme() == "http" || url.scheme() == "https")
+}
+
+// The part of a URL robots.txt rules match against; anything that isn't a
+// URL is taken to be a path already
+fn path_and_query(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) => match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        },
+        Err(_) => url.to_string(),
+    }
+}
+
+fn read_robots_file(path: &str) -> Result<String> {
+    let bytes = std::fs::read(path)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+// The robots.txt at `url` itself, which needn't be the site's own
+async fn fetch_robots_file(checker: &RobotsChecker, url: &str) -> Result<(StatusCode, String)> {
+    let response = checker.client().get(url).send().await?;
+    let code = response.status();
+    Ok((code, response.text().await?))
+}
+
+// What a robots.txt response means, as the crawler takes it (RFC 9309
+// 2.3.1)
+fn robots_status(code: StatusCode) -> RobotsStatus {
+    if code.is_success() {
+        RobotsStatus::Fetched
+    } else if code.is_server_error() || code == StatusCode::TOO_MANY_REQUESTS {
+        RobotsStatus::Unreachable
+    } else {
+        RobotsStatus::Unavailable
+    }
+}
+
+async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
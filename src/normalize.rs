@@ -0,0 +1,379 @@
+// src/normalize.rs
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+// Directory index documents that `collapse_index` folds into their directory
+const INDEX_FILES: &[&str] = &["index.html", "index.htm", "index.php"];
+
+/// What to do with a slash at the end of a URL path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrailingSlash {
+    /// `/docs/` becomes `/docs`
+    #[default]
+    Strip,
+    /// `/docs` becomes `/docs/`; paths ending in a file name are left alone
+    Add,
+    /// Leave paths as they are
+    Keep,
+}
+
+/// How URLs are rewritten before deduplication, so that variants of the same
+/// address are crawled once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NormalizationRules {
+    /// The URL parser already does this for http and https URLs
+    pub lowercase_host: bool,
+    /// Drop `:80` on http and `:443` on https (also done by the URL parser)
+    pub remove_default_port: bool,
+    /// Order query parameters by name
+    pub sort_query: bool,
+    /// Query (and `;` path) parameters to drop, matched case-insensitively
+    /// by name; `*` matches any run of characters
+    pub strip_params: Vec<String>,
+    /// Treat `/dir/index.html` as `/dir/`
+    pub collapse_index: bool,
+    pub trailing_slash: TrailingSlash,
+    pub strip_fragment: bool,
+}
+
+impl Default for NormalizationRules {
+    fn default() -> Self {
+        Self {
+            lowercase_host: true,
+            remove_default_port: true,
+            sort_query: false,
+            strip_params: vec![
+                "utm_*".to_string(),
+                "fbclid".to_string(),
+                "gclid".to_string(),
+                "msclkid".to_string(),
+                "jsessionid".to_string(),
+                "phpsessid".to_string(),
+                "sessionid".to_string(),
+            ],
+            collapse_index: false,
+            trailing_slash: TrailingSlash::Strip,
+            strip_fragment: true,
+        }
+    }
+}
+
+impl NormalizationRules {
+    pub fn normalize(&self, url: &Url) -> String {
+        let mut url = url.clone();
+
+        // Remove fragments (anchors)
+        if self.strip_fragment {
+            url.set_fragment(None);
+        }
+
+        if self.lowercase_host {
+            if let Some(host) = url.host_str().filter(|h| h.chars().any(char::is_uppercase)) {
+                let host = host.to_lowercase();
+                let _ = url.set_host(Some(&host));
+            }
+        }
+
+        if self.remove_default_port && url.port().is_some() && url.port() == default_port(&url) {
+            let _ = url.set_port(None);
+        }
+
+        let path = self.normalize_path(url.path());
+        url.set_path(&path);
+
+        if let Some(query) = url.query() {
+            let query = self.normalize_query(query);
+            url.set_query((!query.is_empty()).then_some(query.as_str()));
+        }
+
+        // Convert to string; the root path can only lose its slash in string
+        // form, and only when nothing follows it
+        let mut url_str = url.to_string();
+        if self.trailing_slash == TrailingSlash::Strip
+            && url.path() == "/"
+            && url.query().is_none()
+            && url.fragment().is_none()
+        {
+            url_str.pop();
+        }
+
+        url_str
+    }
+
+    fn normalize_path(&self, path: &str) -> String {
+        let mut segments: Vec<String> = path
+            .split('/')
+            .map(|segment| self.strip_path_params(segment))
+            .collect();
+
+        if self.collapse_index {
+            if let Some(last) = segments.last_mut() {
+                if INDEX_FILES.iter().any(|f| last.eq_ignore_ascii_case(f)) {
+                    last.clear();
+                }
+            }
+        }
+
+        let mut path = segments.join("/");
+        match self.trailing_slash {
+            TrailingSlash::Strip => {
+                while path.len() > 1 && path.ends_with('/') {
+                    path.pop();
+                }
+            }
+            TrailingSlash::Add => {
+                let last = path.rsplit('/').next().unwrap_or("");
+                if !last.is_empty() && !last.contains('.') {
+                    path.push('/');
+                }
+            }
+            TrailingSlash::Keep => {}
+        }
+
+        path
+    }
+
+    // Drops matching `;name=value` parameters, e.g. `;jsessionid=...`
+    fn strip_path_params(&self, segment: &str) -> String {
+        if !segment.contains(';') {
+            return segment.to_string();
+        }
+
+        let mut parts = segment.split(';');
+        let mut kept = parts.next().unwrap_or("").to_string();
+        for param in parts {
+            let name = param.split('=').next().unwrap_or("");
+            if !self.strips_param(name) {
+                kept.push(';');
+                kept.push_str(param);
+            }
+        }
+        kept
+    }
+
+    // Works on the raw query so the remaining parameters keep their encoding
+    fn normalize_query(&self, query: &str) -> String {
+        let mut params: Vec<&str> = query
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .filter(|param| {
+                url::form_urlencoded::parse(param.as_bytes())
+                    .next()
+                    .is_none_or(|(name, _)| !self.strips_param(&name))
+            })
+            .collect();
+
+        if self.sort_query {
+            params.sort_by_key(|param| param.split('=').next().unwrap_or(""));
+        }
+
+        params.join("&")
+    }
+
+    fn strips_param(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.strip_params
+            .iter()
+            .any(|pattern| wildcard_match(&pattern.to_lowercase(), &name))
+    }
+}
+
+fn default_port(url: &Url) -> Option<u16> {
+    match url.scheme() {
+        "http" => Some(80),
+        "https" => Some(443),
+        _ => None,
+    }
+}
+
+// Glob match where `*` stands for any run of characters
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == text;
+    };
+
+    let Some(mut text) = text.strip_prefix(prefix) else {
+        return false;
+    };
+
+    let mut pieces: Vec<&str> = rest.split('*').collect();
+    let suffix = pieces.pop().unwrap_or("");
+    for piece in pieces {
+        match text.find(piece) {
+            Some(at) => text = &text[at + piece.len()..],
+            None => return false,
+        }
+    }
+
+    text.len() >= suffix.len() && text.ends_with(suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(rules: &NormalizationRules, url: &str) -> String {
+        rules.normalize(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn lowercases_host() {
+        let rules = NormalizationRules::default();
+        assert_eq!(
+            normalize(&rules, "https://Example.COM/Path"),
+            "https://example.com/Path"
+        );
+    }
+
+    #[test]
+    fn removes_default_port_only() {
+        let rules = NormalizationRules::default();
+        assert_eq!(normalize(&rules, "http://a.com:80/x"), "http://a.com/x");
+        assert_eq!(normalize(&rules, "https://a.com:443/x"), "https://a.com/x");
+        assert_eq!(
+            normalize(&rules, "https://a.com:8443/x"),
+            "https://a.com:8443/x"
+        );
+    }
+
+    #[test]
+    fn sorts_query_when_asked() {
+        let mut rules = NormalizationRules::default();
+        assert_eq!(
+            normalize(&rules, "https://a.com/?b=1&a=2"),
+            "https://a.com/?b=1&a=2"
+        );
+
+        rules.sort_query = true;
+        assert_eq!(
+            normalize(&rules, "https://a.com/?b=1&a=2"),
+            "https://a.com/?a=2&b=1"
+        );
+    }
+
+    #[test]
+    fn strips_params_by_pattern() {
+        let rules = NormalizationRules::default();
+        assert_eq!(
+            normalize(
+                &rules,
+                "https://a.com/p?utm_source=x&id=3&UTM_Medium=y&fbclid=z"
+            ),
+            "https://a.com/p?id=3"
+        );
+        assert_eq!(
+            normalize(&rules, "https://a.com/p?utm_source=x"),
+            "https://a.com/p"
+        );
+        assert_eq!(
+            normalize(&rules, "https://a.com/cart;jsessionid=abc;v=2"),
+            "https://a.com/cart;v=2"
+        );
+    }
+
+    #[test]
+    fn wildcards_match_any_run() {
+        assert!(wildcard_match("utm_*", "utm_source"));
+        assert!(wildcard_match("*_id", "session_id"));
+        assert!(wildcard_match("a*b*c", "axxbyyc"));
+        assert!(!wildcard_match("a*b*c", "axxbyy"));
+        assert!(!wildcard_match("utm_*", "xutm_source"));
+    }
+
+    #[test]
+    fn collapses_index_files_when_asked() {
+        let mut rules = NormalizationRules {
+            trailing_slash: TrailingSlash::Keep,
+            ..NormalizationRules::default()
+        };
+        assert_eq!(
+            normalize(&rules, "https://a.com/docs/index.html"),
+            "https://a.com/docs/index.html"
+        );
+
+        rules.collapse_index = true;
+        assert_eq!(
+            normalize(&rules, "https://a.com/docs/INDEX.htm"),
+            "https://a.com/docs/"
+        );
+        assert_eq!(
+            normalize(&rules, "https://a.com/docs/index.html?x=1"),
+            "https://a.com/docs/?x=1"
+        );
+    }
+
+    #[test]
+    fn strips_trailing_slash_from_path_only() {
+        let rules = NormalizationRules::default();
+        assert_eq!(normalize(&rules, "https://a.com/"), "https://a.com");
+        assert_eq!(
+            normalize(&rules, "https://a.com/docs//"),
+            "https://a.com/docs"
+        );
+        assert_eq!(
+            normalize(&rules, "https://a.com/?next=/"),
+            "https://a.com/?next=/"
+        );
+        assert_eq!(
+            normalize(&rules, "https://a.com/search/?q=a/"),
+            "https://a.com/search?q=a/"
+        );
+    }
+
+    #[test]
+    fn adds_trailing_slash_to_directories() {
+        let rules = NormalizationRules {
+            trailing_slash: TrailingSlash::Add,
+            ..NormalizationRules::default()
+        };
+        assert_eq!(
+            normalize(&rules, "https://a.com/docs"),
+            "https://a.com/docs/"
+        );
+        assert_eq!(
+            normalize(&rules, "https://a.com/docs?q=a"),
+            "https://a.com/docs/?q=a"
+        );
+        assert_eq!(
+            normalize(&rules, "https://a.com/docs/page.html"),
+            "https://a.com/docs/page.html"
+        );
+        assert_eq!(normalize(&rules, "https://a.com"), "https://a.com/");
+    }
+
+    #[test]
+    fn keeps_trailing_slash_as_is() {
+        let rules = NormalizationRules {
+            trailing_slash: TrailingSlash::Keep,
+            ..NormalizationRules::default()
+        };
+        assert_eq!(
+            normalize(&rules, "https://a.com/docs/"),
+            "https://a.com/docs/"
+        );
+        assert_eq!(
+            normalize(&rules, "https://a.com/docs"),
+            "https://a.com/docs"
+        );
+        assert_eq!(normalize(&rules, "https://a.com/"), "https://a.com/");
+    }
+
+    #[test]
+    fn strips_fragment_when_asked() {
+        let mut rules = NormalizationRules::default();
+        assert_eq!(normalize(&rules, "https://a.com/p#top"), "https://a.com/p");
+        assert_eq!(normalize(&rules, "https://a.com/#top"), "https://a.com");
+
+        rules.strip_fragment = false;
+        assert_eq!(
+            normalize(&rules, "https://a.com/p#top"),
+            "https://a.com/p#top"
+        );
+        assert_eq!(
+            normalize(&rules, "https://a.com/#top"),
+            "https://a.com/#top"
+        );
+    }
+}
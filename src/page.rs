@@ -2,6 +2,15 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// One step of a redirect chain: `url` answered `status` pointing at
+/// `location`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
+    pub location: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Page {
     pub url: String,
@@ -12,6 +21,58 @@ pub struct Page {
     pub status_code: Option<u16>,
     pub size: Option<usize>,
     pub crawled_at: Option<DateTime<Utc>>,
+    /// URL of the seed this page was discovered from
+    #[serde(default)]
+    pub seed: Option<String>,
+    #[serde(default)]
+    pub seed_tag: Option<String>,
+    /// Number of requests it took to fetch the page
+    #[serde(default)]
+    pub attempts: u32,
+    /// `<lastmod>` from the sitemap that listed this page
+    #[serde(default)]
+    pub sitemap_lastmod: Option<String>,
+    /// `<priority>` from the sitemap that listed this page
+    #[serde(default)]
+    pub sitemap_priority: Option<f32>,
+    /// Redirects followed to reach the content, in order
+    #[serde(default)]
+    pub redirects: Vec<RedirectHop>,
+    /// Where the redirects ended up, if anywhere other than `url`
+    #[serde(default)]
+    pub final_url: Option<String>,
+    /// The `<link rel="canonical">` URL the page declares
+    #[serde(default)]
+    pub canonical: Option<String>,
+    /// SHA-256 of the body, hex-encoded
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// SimHash of the visible text, for near-duplicate detection
+    #[serde(default)]
+    pub simhash: Option<u64>,
+    /// An earlier page with the same or nearly the same content
+    #[serde(default)]
+    pub duplicate_of: Option<String>,
+    /// `ETag` validator from the response
+    #[serde(default)]
+    pub etag: Option<String>,
+    /// `Last-Modified` validator from the response
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    /// The server answered 304 and the page was rebuilt from the previous
+    /// crawl
+    #[serde(default)]
+    pub not_modified: bool,
+    /// Time spent parsing the HTML, in milliseconds
+    #[serde(default)]
+    pub parse_time_ms: Option<f64>,
+    /// Directives such as `noindex` and `nofollow` from the page's meta
+    /// robots tags and `X-Robots-Tag` headers
+    #[serde(default)]
+    pub robots_directives: Vec<String>,
+    /// Links marked `rel="nofollow"`
+    #[serde(default)]
+    pub nofollow_links: Vec<String>,
 }
 
 impl Page {
@@ -25,9 +86,48 @@ impl Page {
             status_code: None,
             size: None,
             crawled_at: None,
+            seed: None,
+            seed_tag: None,
+            attempts: 0,
+            sitemap_lastmod: None,
+            sitemap_priority: None,
+            redirects: Vec::new(),
+            final_url: None,
+            canonical: None,
+            content_hash: None,
+            simhash: None,
+            duplicate_of: None,
+            etag: None,
+            last_modified: None,
+            not_modified: false,
+            parse_time_ms: None,
+            robots_directives: Vec::new(),
+            nofollow_links: Vec::new(),
         }
     }
 
+    /// A page for the same URL, depth and seed, with no fetch results yet.
+    pub fn blank_copy(&self) -> Self {
+        let mut page = Page::new(self.url.clone(), self.depth)
+            .with_seed(self.seed.clone(), self.seed_tag.clone());
+        page.sitemap_lastmod = self.sitemap_lastmod.clone();
+        page.sitemap_priority = self.sitemap_priority;
+        page
+    }
+
+    pub fn with_sitemap_entry(mut self, lastmod: Option<String>, priority: Option<f32>) -> Self {
+        self.sitemap_lastmod = lastmod;
+        self.sitemap_priority = priority;
+        self
+    }
+
+    /// Record the seed this page descends from.
+    pub fn with_seed(mut self, seed: Option<String>, seed_tag: Option<String>) -> Self {
+        self.seed = seed;
+        self.seed_tag = seed_tag;
+        self
+    }
+
     pub fn with_links(mut self, links: Vec<String>) -> Self {
         self.links = links;
         self
@@ -48,6 +148,63 @@ impl Page {
         self
     }
 
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    pub fn with_redirects(
+        mut self,
+        redirects: Vec<RedirectHop>,
+        final_url: Option<String>,
+    ) -> Self {
+        self.redirects = redirects;
+        self.final_url = final_url;
+        self
+    }
+
+    pub fn with_canonical(mut self, canonical: Option<String>) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    pub fn with_fingerprint(
+        mut self,
+        content_hash: String,
+        simhash: Option<u64>,
+        duplicate_of: Option<String>,
+    ) -> Self {
+        self.content_hash = Some(content_hash);
+        self.simhash = simhash;
+        self.duplicate_of = duplicate_of;
+        self
+    }
+
+    pub fn with_validators(mut self, etag: Option<String>, last_modified: Option<String>) -> Self {
+        self.etag = etag;
+        self.last_modified = last_modified;
+        self
+    }
+
+    pub fn with_parse_time(mut self, parse_time: std::time::Duration) -> Self {
+        self.parse_time_ms = Some(parse_time.as_secs_f64() * 1000.0);
+        self
+    }
+
+    pub fn with_directives(
+        mut self,
+        robots_directives: Vec<String>,
+        nofollow_links: Vec<String>,
+    ) -> Self {
+        self.robots_directives = robots_directives;
+        self.nofollow_links = nofollow_links;
+        self
+    }
+
+    pub fn has_directive(&self, directive: &str) -> bool {
+        self.robots_directives.iter().any(|d| d == directive)
+    }
+
     pub fn with_size(mut self, size: usize) -> Self {
         self.size = Some(size);
         self
@@ -0,0 +1,175 @@
+// src/parser.rs
+use log::debug;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use url::Url;
+
+use crate::error::{CrawlerError, Result};
+use crate::fingerprint;
+use crate::normalize::NormalizationRules;
+
+/// What the crawler takes from a page's HTML.
+#[derive(Debug)]
+pub(crate) struct PageContent {
+    pub links: Vec<String>,
+    /// Links only ever marked `rel="nofollow"` on the page
+    pub nofollow_links: Vec<String>,
+    /// Every `<meta name content>` pair, names lowercased; the crawler picks
+    /// out the robots directives meant for it
+    pub meta: Vec<(String, String)>,
+    pub title: Option<String>,
+    pub canonical: Option<String>,
+    pub content_hash: String,
+    pub simhash: Option<u64>,
+}
+
+/// Threads that parse fetched pages, so a large document doesn't hold up
+/// the async runtime and the other fetches running on it.
+#[derive(Clone)]
+pub struct ParsePool {
+    pool: Arc<ThreadPool>,
+}
+
+impl ParsePool {
+    /// A pool of `threads` parser threads, or one per CPU when `None`.
+    pub fn new(threads: Option<usize>) -> Result<Self> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads.unwrap_or(0))
+            .thread_name(|i| format!("crawler-parse-{}", i))
+            .build()
+            .map_err(|e| CrawlerError::ConfigError(format!("Parser thread pool: {}", e)))?;
+
+        Ok(Self {
+            pool: Arc::new(pool),
+        })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Parse a response body on the pool. Also returns how long the parse
+    /// itself took, not counting time spent waiting for a free thread.
+    pub(crate) async fn parse<B>(
+        &self,
+        body: B,
+        base_url: Url,
+        rules: NormalizationRules,
+    ) -> Result<(PageContent, Duration)>
+    where
+        B: AsRef<[u8]> + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.pool.spawn(move || {
+            let started = Instant::now();
+            let content = extract(body.as_ref(), &base_url, &rules);
+            let _ = tx.send((content, started.elapsed()));
+        });
+
+        // The sender only goes away without sending if the parser panicked
+        rx.await
+            .map_err(|_| CrawlerError::ParseError("parser thread panicked".to_string()))
+    }
+}
+
+/// Pull links, title, canonical URL and fingerprints out of an HTML body.
+/// Relative links resolve against `base_url`.
+pub(crate) fn extract(body: &[u8], base_url: &Url, rules: &NormalizationRules) -> PageContent {
+    let html_text = String::from_utf8_lossy(body);
+    let document = Html::parse_document(&html_text);
+
+    // Extract page title
+    let title = document
+        .select(&Selector::parse("title").unwrap())
+        .next()
+        .and_then(|el| el.text().next())
+        .map(|s| s.to_string());
+
+    let selector = Selector::parse("a[href]").unwrap();
+
+    // Extract and validate links
+    let mut links = Vec::new();
+    let mut nofollow = HashSet::new();
+    let mut followable = HashSet::new();
+
+    for element in document.select(&selector) {
+        if let Some(href) = element.value().attr("href") {
+            // Convert relative URLs to absolute
+            if let Ok(absolute_url) = base_url.join(href) {
+                // Only accept HTTP(S) links
+                if absolute_url.scheme() == "http" || absolute_url.scheme() == "https" {
+                    // Normalize the URL to avoid duplicates
+                    let link = rules.normalize(&absolute_url);
+                    if has_rel(element.value().attr("rel"), "nofollow") {
+                        nofollow.insert(link.clone());
+                    } else {
+                        followable.insert(link.clone());
+                    }
+                    links.push(link);
+                }
+            }
+        }
+    }
+
+    // A target linked without nofollow anywhere on the page may be followed
+    let mut nofollow_links: Vec<String> = nofollow.difference(&followable).cloned().collect();
+    nofollow_links.sort();
+
+    let meta = document
+        .select(&Selector::parse("meta[name][content]").unwrap())
+        .filter_map(|el| {
+            let name = el.value().attr("name")?.trim().to_ascii_lowercase();
+            Some((name, el.value().attr("content")?.to_string()))
+        })
+        .collect();
+
+    // The canonical URL the page declares for itself, if any
+    let canonical = document
+        .select(&Selector::parse("link[rel][href]").unwrap())
+        .find(|el| has_rel(el.value().attr("rel"), "canonical"))
+        .and_then(|el| base_url.join(el.value().attr("href")?.trim()).ok())
+        .filter(|url| url.scheme() == "http" || url.scheme() == "https")
+        .map(|url| rules.normalize(&url));
+
+    // Fingerprint the visible text, so template tweaks and timestamps in
+    // markup don't hide a copy
+    let mut visible_text = String::new();
+    if let Some(body) = document.select(&Selector::parse("body").unwrap()).next() {
+        for node in body.descendants() {
+            let Some(text) = node.value().as_text() else {
+                continue;
+            };
+            let hidden = node
+                .parent()
+                .and_then(|p| p.value().as_element().map(|el| el.name()))
+                .is_some_and(|name| matches!(name, "script" | "style" | "noscript"));
+            if !hidden {
+                visible_text.push_str(text);
+                visible_text.push(' ');
+            }
+        }
+    }
+
+    debug!("✨ Found {} valid links on {}", links.len(), base_url);
+    PageContent {
+        links,
+        nofollow_links,
+        meta,
+        title,
+        canonical,
+        content_hash: fingerprint::content_hash(body),
+        simhash: (!visible_text.trim().is_empty()).then(|| fingerprint::simhash(&visible_text)),
+    }
+}
+
+// Whether a `rel` attribute lists `value` among its space-separated keywords
+fn has_rel(rel: Option<&str>, value: &str) -> bool {
+    rel.is_some_and(|rel| {
+        rel.split_whitespace()
+            .any(|r| r.eq_ignore_ascii_case(value))
+    })
+}
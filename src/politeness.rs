@@ -0,0 +1,151 @@
+// src/politeness.rs
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use url::Url;
+
+use crate::page::Page;
+
+/// Per-host politeness scheduler.
+///
+/// Keeps the earliest time the next request to each host may be sent. Callers
+/// take a slot with [`HostScheduler::try_take_turn`], or reserve one and wait
+/// for it with [`HostScheduler::wait_turn`], before fetching; slots for one
+/// host are spaced `delay` apart, while other hosts are unaffected.
+#[derive(Debug, Clone, Default)]
+pub struct HostScheduler {
+    next_allowed: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl HostScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve the next fetch slot for `host` and sleep until it arrives.
+    pub async fn wait_turn(&self, host: &str, delay: Duration) {
+        let slot = self.reserve(host, delay).await;
+        tokio::time::sleep_until(slot).await;
+    }
+
+    /// Take `host`'s slot if it is due now, pushing the next one `delay`
+    /// later. Otherwise nothing is reserved and the time the slot comes due
+    /// is returned, so the caller can do something else meanwhile.
+    pub async fn try_take_turn(&self, host: &str, delay: Duration) -> Result<(), Instant> {
+        let now = Instant::now();
+        let mut next_allowed = self.next_allowed.lock().await;
+        let next = next_allowed.entry(host.to_string()).or_insert(now);
+
+        if *next > now {
+            return Err(*next);
+        }
+        *next = now + delay;
+        Ok(())
+    }
+
+    /// Keep `host` idle for at least `delay` from now, e.g. after a 429 or
+    /// 503. Slots already handed out are not moved.
+    pub async fn back_off(&self, host: &str, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut next_allowed = self.next_allowed.lock().await;
+        let next = next_allowed.entry(host.to_string()).or_insert(until);
+        *next = (*next).max(until);
+    }
+
+    async fn reserve(&self, host: &str, delay: Duration) -> Instant {
+        let now = Instant::now();
+        let mut next_allowed = self.next_allowed.lock().await;
+        let next = next_allowed.entry(host.to_string()).or_insert(now);
+
+        // The host may have been idle for a while, in which case we can go now
+        let slot = (*next).max(now);
+        *next = slot + delay;
+        slot
+    }
+}
+
+/// Pages taken from the frontier before their host was due, held back
+/// until it is. Each host keeps its pages in the order they were taken.
+#[derive(Debug, Default)]
+pub struct ParkedPages {
+    // When each host is due next, and its waiting pages
+    hosts: HashMap<String, (Instant, VecDeque<Page>)>,
+    len: usize,
+}
+
+impl ParkedPages {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hand back a page that missed its host's slot. It is first in line
+    /// when the host is due at `due`.
+    pub fn park(&mut self, host: String, due: Instant, page: Page) {
+        let (host_due, pages) = self
+            .hosts
+            .entry(host)
+            .or_insert_with(|| (due, VecDeque::new()));
+        *host_due = due;
+        pages.push_front(page);
+        self.len += 1;
+    }
+
+    /// Line `page` up behind the pages already parked for its host, so a
+    /// host's pages are fetched in the order they were taken. Returns the
+    /// page if its host has none parked.
+    pub fn line_up(&mut self, host: &str, page: Page) -> Option<Page> {
+        match self.hosts.get_mut(host) {
+            Some((_, pages)) => {
+                pages.push_back(page);
+                self.len += 1;
+                None
+            }
+            None => Some(page),
+        }
+    }
+
+    /// The oldest page of the host that has been due the longest, if any
+    /// host is due by `now`.
+    pub fn take_due(&mut self, now: Instant) -> Option<Page> {
+        let host = self
+            .hosts
+            .iter()
+            .filter(|(_, (due, _))| *due <= now)
+            .min_by_key(|(_, (due, _))| *due)
+            .map(|(host, _)| host.clone())?;
+
+        let (_, pages) = self.hosts.get_mut(&host)?;
+        let page = pages.pop_front();
+        if pages.is_empty() {
+            self.hosts.remove(&host);
+        }
+        self.len -= 1;
+        page
+    }
+
+    /// When the next parked page's host comes due.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.hosts.values().map(|(due, _)| *due).min()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// The key politeness is tracked under: host plus explicit port, so two
+/// servers on the same machine are scheduled independently.
+pub fn host_key(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+    Some(match parsed.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    })
+}
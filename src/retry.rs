@@ -0,0 +1,103 @@
+// src/retry.rs
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::config::duration_serde;
+
+/// Transport failures that may be worth another attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryableError {
+    /// The request timed out
+    Timeout,
+    /// Connecting to the server failed
+    Connect,
+    /// The request failed after connecting, e.g. the connection was reset
+    Request,
+}
+
+/// When and how often a failed fetch is retried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total attempts per URL, including the first (1 disables retries)
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles with each further retry
+    #[serde(with = "duration_serde")]
+    pub backoff_base: Duration,
+    /// Upper bound for the backoff, and for how long a `Retry-After` may
+    /// ask us to wait before we give up on the URL instead
+    #[serde(with = "duration_serde")]
+    pub max_backoff: Duration,
+    /// Random spread applied to each backoff, as a fraction (0.2 = ±20%)
+    pub jitter: f64,
+    pub retry_statuses: Vec<u16>,
+    pub retry_errors: Vec<RetryableError>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff_base: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: 0.2,
+            retry_statuses: vec![429, 503],
+            retry_errors: vec![
+                RetryableError::Timeout,
+                RetryableError::Connect,
+                RetryableError::Request,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn retries_status(&self, status: u16) -> bool {
+        self.retry_statuses.contains(&status)
+    }
+
+    pub fn retries_error(&self, error: &reqwest::Error) -> bool {
+        self.retry_errors.iter().any(|kind| match kind {
+            RetryableError::Timeout => error.is_timeout(),
+            RetryableError::Connect => error.is_connect(),
+            RetryableError::Request => error.is_request(),
+        })
+    }
+
+    /// Backoff before attempt `attempt + 1`, given that `attempt` (1-based)
+    /// just failed.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let delay = self
+            .backoff_base
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+
+        if self.jitter <= 0.0 {
+            return delay;
+        }
+
+        let spread = self.jitter.min(1.0);
+        let factor = rand::thread_rng().gen_range(1.0 - spread..=1.0 + spread);
+        delay.mul_f64(factor).min(self.max_backoff)
+    }
+}
+
+/// Reads a `Retry-After` header given either as delay-seconds or as an
+/// HTTP-date.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let at = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((at - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+}
@@ -1,185 +1,438 @@
 // src/robots.rs
-use log::{debug, warn};
-use reqwest::Client;
+use log::{debug, error, warn};
+use redb::{Database, TableDefinition};
+use reqwest::header::CACHE_CONTROL;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use url::Url;
 
+use crate::builder::build_client;
+use crate::config::{duration_serde, CrawlerConfig};
 use crate::error::{CrawlerError, Result};
+use crate::robots_txt::RobotsTxt;
 
-#[derive(Debug, Clone)]
-pub struct RobotsChecker {
-    client: Client,
-    cache: Arc<RwLock<HashMap<String, RobotsData>>>,
+const ROBOTS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("robots");
+
+/// How robots.txt files are fetched and how long they are reused.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RobotsConfig {
+    /// Redirect hops followed when fetching robots.txt. A longer chain
+    /// counts as a site without robots.txt.
+    pub max_redirects: usize,
+    /// Shortest time a robots.txt is reused, even if Cache-Control asks for
+    /// less. Also how long an unreachable site stays disallowed before its
+    /// robots.txt is tried again.
+    #[serde(with = "duration_serde")]
+    pub min_ttl: Duration,
+    /// Longest time a robots.txt is reused, and how long one without a
+    /// Cache-Control max-age is kept
+    #[serde(with = "duration_serde")]
+    pub max_ttl: Duration,
+    /// Database file keeping fetched robots.txt files between runs (kept in
+    /// memory only when unset)
+    pub cache_path: Option<String>,
+}
+
+impl Default for RobotsConfig {
+    fn default() -> Self {
+        Self {
+            max_redirects: 5,
+            min_ttl: Duration::from_secs(60),
+            max_ttl: Duration::from_secs(24 * 60 * 60),
+            cache_path: None,
+        }
+    }
+}
+
+/// What fetching a site's robots.txt came back with, following RFC 9309.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RobotsStatus {
+    /// A 2xx response: its rules apply
+    Fetched,
+    /// A 4xx response (other than 429) or too many redirects: the site has
+    /// no robots.txt, so everything is allowed
+    Unavailable,
+    /// A 5xx or 429 response, a timeout or a network error: everything is
+    /// disallowed until the file can be fetched
+    Unreachable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    status: RobotsStatus,
+    /// The robots.txt body; empty unless fetched
+    body: String,
+    /// Unix time in seconds after which the file is fetched again
+    expires_at: u64,
+    #[serde(skip)]
+    robots: Arc<RobotsTxt>,
+}
+
+impl CacheEntry {
+    fn new(status: RobotsStatus, body: String, ttl: Duration) -> Self {
+        let mut entry = Self {
+            status,
+            body,
+            expires_at: unix_now() + ttl.as_secs(),
+            robots: Arc::default(),
+        };
+        entry.parse();
+        entry
+    }
+
+    // Rebuild the rules, which aren't stored with the entry
+    fn parse(&mut self) {
+        self.robots = Arc::new(match self.status {
+            RobotsStatus::Fetched => RobotsTxt::parse(&self.body),
+            RobotsStatus::Unavailable => RobotsTxt::default(),
+            RobotsStatus::Unreachable => RobotsTxt::disallow_all(),
+        });
+    }
+
+    fn is_fresh(&self) -> bool {
+        unix_now() < self.expires_at
+    }
 }
 
 #[derive(Debug, Clone)]
-struct RobotsData {
-    allow_patterns: Vec<String>,
-    disallow_patterns: Vec<String>,
-    crawl_delay: Option<f64>,
+pub struct RobotsChecker {
+    client: Client,
+    config: RobotsConfig,
+    cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
+    store: Option<Arc<Database>>,
 }
 
 impl RobotsChecker {
+    /// A checker with the default [`RobotsConfig`], fetching with `client`.
     pub fn new(client: Client) -> Self {
         Self {
             client,
+            config: RobotsConfig::default(),
             cache: Arc::new(RwLock::new(HashMap::new())),
+            store: None,
         }
     }
 
+    /// A checker with the given TTLs, opening the persistent cache if one is
+    /// configured. `client` should follow up to `config.max_redirects`
+    /// redirects.
+    pub fn with_config(client: Client, config: RobotsConfig) -> Result<Self> {
+        let store = match &config.cache_path {
+            Some(path) => Some(Arc::new(open_store(path)?)),
+            None => None,
+        };
+
+        Ok(Self {
+            client,
+            config,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            store,
+        })
+    }
+
+    /// A checker fetching with the crawler's user agent and request timeout,
+    /// cached as `config.robots` says.
+    pub fn from_config(config: &CrawlerConfig) -> Result<Self> {
+        // robots.txt is commonly redirected (e.g. to https), so the client
+        // follows
+        let client = build_client(
+            config,
+            reqwest::redirect::Policy::limited(config.robots.max_redirects),
+        )?;
+        Self::with_config(client, config.robots.clone())
+    }
+
+    /// The client robots.txt files are fetched with.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
     pub async fn is_allowed(&self, url: &str, user_agent: &str) -> Result<bool> {
         let parsed_url = Url::parse(url)
             .map_err(|e| CrawlerError::RobotsError(format!("Failed to parse URL: {}", e)))?;
 
-        let domain = format!(
-            "{}://{}",
-            parsed_url.scheme(),
-            parsed_url.host_str().unwrap_or_default()
-        );
+        // Rules match against the path and query
+        let path = match parsed_url.query() {
+            Some(query) => format!("{}?{}", parsed_url.path(), query),
+            None => parsed_url.path().to_string(),
+        };
+
+        let robots = self.robots_data(&site_key(&parsed_url)).await;
+        let verdict = robots.check(&path, user_agent);
+        if let Some(rule) = verdict.rule.filter(|_| !verdict.allowed) {
+            debug!("{} disallowed by \"{}\" (line {})", url, rule, rule.line);
+        }
+        Ok(verdict.allowed)
+    }
+
+    /// Sitemap URLs advertised in the robots.txt of `url`'s site.
+    pub async fn sitemaps(&self, url: &str) -> Result<Vec<String>> {
+        let parsed_url = Url::parse(url)
+            .map_err(|e| CrawlerError::RobotsError(format!("Failed to parse URL: {}", e)))?;
+
+        Ok(self
+            .robots_data(&site_key(&parsed_url))
+            .await
+            .sitemaps
+            .clone())
+    }
+
+    /// The robots.txt applying to `url`'s site, fetched if it isn't cached.
+    /// A site without one, or one that can't be reached, gives the rules
+    /// that stand in for it; [`RobotsChecker::status`] tells them apart.
+    pub async fn robots_txt(&self, url: &str) -> Result<Arc<RobotsTxt>> {
+        let parsed_url = Url::parse(url)
+            .map_err(|e| CrawlerError::RobotsError(format!("Failed to parse URL: {}", e)))?;
 
-        let robots_url = format!("{}/robots.txt", domain);
-        let path = parsed_url.path();
+        Ok(self.robots_data(&site_key(&parsed_url)).await)
+    }
 
-        // Check cache first
-        {
+    /// What the last fetch of `url`'s robots.txt came back with, if it is
+    /// cached.
+    pub async fn status(&self, url: &str) -> Option<RobotsStatus> {
+        let parsed_url = Url::parse(url).ok()?;
+        let cache = self.cache.read().await;
+        cache.get(&site_key(&parsed_url)).map(|entry| entry.status)
+    }
+
+    // Cached robots.txt for a site, fetched on first use and again once the
+    // cached copy expires
+    async fn robots_data(&self, site: &str) -> Arc<RobotsTxt> {
+        // Check cache first, then the persistent cache
+        let cached = {
             let cache = self.cache.read().await;
-            if let Some(robots_data) = cache.get(&domain) {
-                return Ok(self.check_path_allowed(robots_data, path));
+            match cache.get(site) {
+                Some(entry) if entry.is_fresh() => return Arc::clone(&entry.robots),
+                entry => entry.cloned(),
             }
+        };
+        let cached = cached.or_else(|| self.load(site));
+        if let Some(entry) = cached.as_ref().filter(|entry| entry.is_fresh()) {
+            let robots = Arc::clone(&entry.robots);
+            let mut cache = self.cache.write().await;
+            cache.insert(site.to_string(), entry.clone());
+            return robots;
         }
 
         // Fetch and parse robots.txt
+        let robots_url = format!("{}/robots.txt", site);
         debug!("Fetching robots.txt from {}", robots_url);
-        let robots_data = match self.fetch_and_parse_robots(&robots_url, user_agent).await {
-            Ok(data) => data,
-            Err(e) => {
-                warn!("Error fetching robots.txt: {}, assuming allowed", e);
-                RobotsData {
-                    allow_patterns: vec![],
-                    disallow_patterns: vec![],
-                    crawl_delay: None,
-                }
+        let mut entry = self.fetch_robots(&robots_url).await;
+
+        // RFC 9309 lets a crawler keep using its last copy while the file
+        // can't be reached
+        if entry.status == RobotsStatus::Unreachable {
+            if let Some(stale) = cached.filter(|entry| entry.status == RobotsStatus::Fetched) {
+                warn!(
+                    "Using expired robots.txt for {} until it can be fetched",
+                    site
+                );
+                entry = CacheEntry {
+                    expires_at: entry.expires_at,
+                    ..stale
+                };
             }
-        };
+        }
 
         // Cache the result
+        self.save(site, &entry).await;
+        let robots = Arc::clone(&entry.robots);
         {
             let mut cache = self.cache.write().await;
-            cache.insert(domain, robots_data.clone());
+            cache.insert(site.to_string(), entry);
         }
 
-        Ok(self.check_path_allowed(&robots_data, path))
+        robots
     }
 
-    fn check_path_allowed(&self, robots_data: &RobotsData, path: &str) -> bool {
-        // Check if path matches any disallow pattern
-        for pattern in &robots_data.disallow_patterns {
-            if path.starts_with(pattern) {
-                // Check if there's a more specific allow pattern
-                for allow_pattern in &robots_data.allow_patterns {
-                    if path.starts_with(allow_pattern) && allow_pattern.len() > pattern.len() {
-                        return true;
-                    }
-                }
-                return false;
+    async fn fetch_robots(&self, robots_url: &str) -> CacheEntry {
+        let unreachable = || {
+            CacheEntry::new(
+                RobotsStatus::Unreachable,
+                String::new(),
+                self.config.min_ttl,
+            )
+        };
+
+        let response = match self.client.get(robots_url).send().await {
+            Ok(response) => response,
+            // Giving up on a redirect chain means there's no file to follow
+            Err(e) if e.is_redirect() => {
+                debug!("Too many redirects for {}, assuming allowed", robots_url);
+                return CacheEntry::new(
+                    RobotsStatus::Unavailable,
+                    String::new(),
+                    self.config.max_ttl,
+                );
+            }
+            Err(e) => {
+                warn!("Error fetching {}: {}, assuming disallowed", robots_url, e);
+                return unreachable();
             }
+        };
+
+        let status = response.status();
+        let ttl = self.ttl(response.headers().get(CACHE_CONTROL));
+
+        if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            warn!(
+                "{} answered {}, assuming disallowed",
+                robots_url,
+                status.as_u16()
+            );
+            return unreachable();
         }
 
-        // If no disallow pattern matches, it's allowed
-        true
-    }
-
-    async fn fetch_and_parse_robots(
-        &self,
-        robots_url: &str,
-        user_agent: &str,
-    ) -> Result<RobotsData> {
-        let response =
-            self.client.get(robots_url).send().await.map_err(|e| {
-                CrawlerError::RobotsError(format!("Failed to fetch robots.txt: {}", e))
-            })?;
-
-        if !response.status().is_success() {
-            // If robots.txt doesn't exist or can't be retrieved, everything is allowed
-            return Ok(RobotsData {
-                allow_patterns: vec![],
-                disallow_patterns: vec![],
-                crawl_delay: None,
-            });
+        if !status.is_success() {
+            // If robots.txt doesn't exist, everything is allowed
+            debug!(
+                "{} answered {}, assuming allowed",
+                robots_url,
+                status.as_u16()
+            );
+            return CacheEntry::new(RobotsStatus::Unavailable, String::new(), ttl);
         }
 
-        let content = response
-            .text()
-            .await
-            .map_err(|e| CrawlerError::RobotsError(format!("Failed to read robots.txt: {}", e)))?;
+        match response.text().await {
+            Ok(content) => CacheEntry::new(RobotsStatus::Fetched, content, ttl),
+            Err(e) => {
+                warn!("Error reading {}: {}, assuming disallowed", robots_url, e);
+                unreachable()
+            }
+        }
+    }
+
+    // How long a response may be reused: its Cache-Control max-age, kept
+    // between the configured bounds
+    fn ttl(&self, cache_control: Option<&reqwest::header::HeaderValue>) -> Duration {
+        let max_age = cache_control
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_max_age);
 
-        // Parse robots.txt
-        let mut current_agent = String::new();
-        let mut allow_patterns = Vec::new();
-        let mut disallow_patterns = Vec::new();
-        let mut crawl_delay = None;
+        max_age.unwrap_or(self.config.max_ttl).clamp(
+            self.config.min_ttl,
+            self.config.max_ttl.max(self.config.min_ttl),
+        )
+    }
 
-        for line in content.lines() {
-            let line = line.trim();
+    // Entry for a site from the persistent cache, if there is one
+    fn load(&self, site: &str) -> Option<CacheEntry> {
+        let store = self.store.as_ref()?;
+        let read = || -> Result<Option<CacheEntry>> {
+            let txn = store.begin_read().map_err(storage_error)?;
+            let table = txn.open_table(ROBOTS_TABLE).map_err(storage_error)?;
+            let Some(value) = table.get(site).map_err(storage_error)? else {
+                return Ok(None);
+            };
+            let mut entry: CacheEntry =
+                serde_json::from_slice(value.value()).map_err(storage_error)?;
+            entry.parse();
+            Ok(Some(entry))
+        };
 
-            // Skip comments and empty lines
-            if line.is_empty() || line.starts_with('#') {
-                continue;
+        read()
+            .map_err(|e| error!("Robots cache read failed for {}: {}", site, e))
+            .ok()
+            .flatten()
+    }
+
+    // Write an entry to the persistent cache. redb commits synchronously, so
+    // the write runs on the blocking pool
+    async fn save(&self, site: &str, entry: &CacheEntry) {
+        let Some(store) = self.store.as_ref().map(Arc::clone) else {
+            return;
+        };
+        let value = match serde_json::to_vec(entry) {
+            Ok(value) => value,
+            Err(e) => {
+                error!("Robots cache write failed for {}: {}", site, e);
+                return;
             }
+        };
 
-            if let Some((key, value)) = line.split_once(':') {
-                let key = key.trim().to_lowercase();
-                let value = value.trim();
-
-                match key.as_str() {
-                    "user-agent" => {
-                        current_agent = value.to_string();
-                    }
-                    "allow" => {
-                        if current_agent == "*" || current_agent == user_agent {
-                            allow_patterns.push(value.to_string());
-                        }
-                    }
-                    "disallow" => {
-                        if current_agent == "*" || current_agent == user_agent {
-                            if !value.is_empty() {
-                                disallow_patterns.push(value.to_string());
-                            }
-                        }
-                    }
-                    "crawl-delay" => {
-                        if current_agent == "*" || current_agent == user_agent {
-                            if let Ok(delay) = value.parse::<f64>() {
-                                crawl_delay = Some(delay);
-                            }
-                        }
-                    }
-                    _ => {}
-                }
+        let key = site.to_string();
+        let write = move || -> Result<()> {
+            let txn = store.begin_write().map_err(storage_error)?;
+            {
+                let mut table = txn.open_table(ROBOTS_TABLE).map_err(storage_error)?;
+                table
+                    .insert(key.as_str(), value.as_slice())
+                    .map_err(storage_error)?;
             }
+            txn.commit().map_err(storage_error)
+        };
+
+        let written = tokio::task::spawn_blocking(write)
+            .await
+            .map_err(storage_error)
+            .and_then(|written| written);
+        if let Err(e) = written {
+            error!("Robots cache write failed for {}: {}", site, e);
         }
+    }
 
-        Ok(RobotsData {
-            allow_patterns,
-            disallow_patterns,
-            crawl_delay,
-        })
+    /// How long `url`'s site asks `user_agent` to wait between requests,
+    /// from its `Crawl-delay` or `Request-rate`. Fetches the robots.txt if
+    /// it isn't cached yet.
+    pub async fn get_crawl_delay(&self, url: &str, user_agent: &str) -> Option<Duration> {
+        let parsed_url = Url::parse(url).ok()?;
+        let delay = self
+            .robots_data(&site_key(&parsed_url))
+            .await
+            .min_interval(user_agent)?;
+        Duration::try_from_secs_f64(delay).ok()
     }
+}
 
-    // Fixed: Added underscore to unused parameter name
-    pub async fn get_crawl_delay(&self, domain: &str, _user_agent: &str) -> Option<Duration> {
-        // Return the crawl delay if available
-        let cache = self.cache.read().await;
-        if let Some(robots_data) = cache.get(domain) {
-            if let Some(delay) = robots_data.crawl_delay {
-                return Some(Duration::from_secs_f64(delay));
-            }
-        }
+// Sites are told apart by scheme, host and port: each has its own robots.txt
+fn site_key(url: &Url) -> String {
+    url.origin().ascii_serialization()
+}
 
-        None
+// `max-age` from a Cache-Control header; `no-cache` and `no-store` count as
+// zero
+fn parse_max_age(cache_control: &str) -> Option<Duration> {
+    let mut max_age = None;
+    for directive in cache_control.split(',') {
+        let directive = directive.trim().to_ascii_lowercase();
+        if directive == "no-cache" || directive == "no-store" {
+            return Some(Duration::ZERO);
+        }
+        if let Some(seconds) = directive.strip_prefix("max-age=") {
+            max_age = seconds
+                .trim_matches('"')
+                .parse()
+                .ok()
+                .map(Duration::from_secs);
+        }
     }
+    max_age
+}
+
+fn open_store(path: &str) -> Result<Database> {
+    let db = Database::create(Path::new(path)).map_err(storage_error)?;
+
+    // Make sure the table exists before the first read
+    let txn = db.begin_write().map_err(storage_error)?;
+    txn.open_table(ROBOTS_TABLE).map_err(storage_error)?;
+    txn.commit().map_err(storage_error)?;
+
+    Ok(db)
 }
 
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+fn storage_error<E: std::fmt::Display>(e: E) -> CrawlerError {
+    CrawlerError::StorageError(format!("Robots cache: {}", e))
+}
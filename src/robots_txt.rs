@@ -0,0 +1,515 @@
+// src/robots_txt.rs
+//! robots.txt parsing and matching as specified by RFC 9309.
+use std::fmt;
+
+// RFC 9309 asks crawlers to parse at least 500 KiB; anything after that is
+// ignored
+const MAX_ROBOTS_BYTES: usize = 500 * 1024;
+
+/// A parsed robots.txt file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsTxt {
+    pub groups: Vec<Group>,
+    /// `Sitemap:` URLs, which apply whatever the user agent
+    pub sitemaps: Vec<String>,
+}
+
+/// The rules for one or more user agents: consecutive `User-agent` lines
+/// followed by the lines up to the next `User-agent` line after a rule.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Group {
+    /// Lowercased product tokens, `*` for the catch-all group
+    pub user_agents: Vec<String>,
+    pub rules: Vec<Rule>,
+    /// `Crawl-delay` in seconds (not part of RFC 9309, but widely used)
+    pub crawl_delay: Option<f64>,
+    /// `Request-rate`, the older way of asking for the same
+    pub request_rate: Option<RequestRate>,
+}
+
+/// At most `requests` requests every `seconds` seconds, from a
+/// `Request-rate: 1/5s` line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RequestRate {
+    pub requests: u32,
+    pub seconds: f64,
+}
+
+impl RequestRate {
+    /// Parse `requests/period`, where the period may end in `s`, `m`, `h` or
+    /// `d` (seconds when bare). A visit-time window after it (`1/5s
+    /// 0800-1700`) is ignored, so the rate applies all day.
+    pub fn parse(value: &str) -> Option<Self> {
+        let rate = value.split_whitespace().next()?;
+        let (requests, period) = rate.split_once('/')?;
+        let requests: u32 = requests.trim().parse().ok().filter(|&n| n > 0)?;
+
+        let period = period.trim().to_ascii_lowercase();
+        let (number, unit) = match period.char_indices().last()? {
+            (i, c) if c.is_ascii_alphabetic() => (&period[..i], c),
+            _ => (period.as_str(), 's'),
+        };
+        let multiplier = match unit {
+            's' => 1.0,
+            'm' => 60.0,
+            'h' => 60.0 * 60.0,
+            'd' => 24.0 * 60.0 * 60.0,
+            _ => return None,
+        };
+        let seconds = number.parse::<f64>().ok()? * multiplier;
+        if !seconds.is_finite() || seconds < 0.0 {
+            return None;
+        }
+
+        Some(Self { requests, seconds })
+    }
+
+    /// Seconds to leave between two requests.
+    pub fn interval(&self) -> f64 {
+        self.seconds / self.requests as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub allow: bool,
+    /// The path pattern as written, percent-encoding normalized
+    pub pattern: String,
+    /// 1-based line in the robots.txt file
+    pub line: usize,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let directive = if self.allow { "Allow" } else { "Disallow" };
+        write!(f, "{}: {}", directive, self.pattern)
+    }
+}
+
+/// The outcome of matching a path: whether it may be crawled, and the rule
+/// that decided it (`None` when no rule matched).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Verdict<'a> {
+    pub allowed: bool,
+    pub rule: Option<&'a Rule>,
+}
+
+impl RobotsTxt {
+    /// Parse a robots.txt body. Parsing never fails: lines that can't be
+    /// understood are skipped, as RFC 9309 requires.
+    pub fn parse(content: &str) -> Self {
+        let content = prepare(content);
+
+        let mut robots = RobotsTxt::default();
+        // The group being built, and whether it has seen a rule yet: a
+        // `User-agent` line after a rule starts a new group
+        let mut current: Option<Group> = None;
+        let mut in_rules = false;
+
+        for (index, raw_line) in content.lines().enumerate() {
+            let Some((key, value)) = strip_comment(raw_line).split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if in_rules || current.is_none() {
+                        robots.groups.extend(current.take());
+                        current = Some(Group::default());
+                        in_rules = false;
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.user_agents.push(agent_token(value));
+                    }
+                }
+                directive @ ("allow" | "disallow") => {
+                    // Rules outside any group don't apply to anyone
+                    let Some(group) = current.as_mut() else {
+                        continue;
+                    };
+                    in_rules = true;
+                    // An empty pattern matches nothing
+                    if !value.is_empty() {
+                        group.rules.push(Rule {
+                            allow: directive == "allow",
+                            pattern: normalize_encoding(value),
+                            line: index + 1,
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    let Some(group) = current.as_mut() else {
+                        continue;
+                    };
+                    in_rules = true;
+                    if let Ok(delay) = value.parse::<f64>() {
+                        if delay.is_finite() && delay >= 0.0 {
+                            group.crawl_delay = Some(delay);
+                        }
+                    }
+                }
+                "request-rate" => {
+                    let Some(group) = current.as_mut() else {
+                        continue;
+                    };
+                    in_rules = true;
+                    if let Some(rate) = RequestRate::parse(value) {
+                        group.request_rate = Some(rate);
+                    }
+                }
+                // Sitemap lines stand outside the groups and don't end one
+                "sitemap" if !value.is_empty() => {
+                    robots.sitemaps.push(value.to_string());
+                }
+                _ => {}
+            }
+        }
+        robots.groups.extend(current);
+
+        robots
+    }
+
+    /// Rules that disallow everything for every user agent, for a site whose
+    /// robots.txt can't be reached.
+    pub fn disallow_all() -> Self {
+        RobotsTxt {
+            groups: vec![Group {
+                user_agents: vec!["*".to_string()],
+                rules: vec![Rule {
+                    allow: false,
+                    pattern: "/".to_string(),
+                    line: 0,
+                }],
+                crawl_delay: None,
+                request_rate: None,
+            }],
+            sitemaps: Vec::new(),
+        }
+    }
+
+    /// The groups that apply to `user_agent`: every group naming its product
+    /// token (case-insensitively), or else every `*` group.
+    pub fn groups_for(&self, user_agent: &str) -> Vec<&Group> {
+        let token = agent_token(user_agent);
+        let named: Vec<&Group> = self
+            .groups
+            .iter()
+            .filter(|group| group.user_agents.contains(&token))
+            .collect();
+        if !named.is_empty() {
+            return named;
+        }
+
+        self.groups
+            .iter()
+            .filter(|group| group.user_agents.iter().any(|agent| agent == "*"))
+            .collect()
+    }
+
+    /// Match a URL path (with its query, if any) against the rules for
+    /// `user_agent`. The longest matching pattern wins; between an Allow and
+    /// a Disallow of the same length, Allow wins. `/robots.txt` itself is
+    /// always allowed.
+    pub fn check(&self, path: &str, user_agent: &str) -> Verdict<'_> {
+        let path = normalize_path(path);
+        if path == "/robots.txt" {
+            return Verdict {
+                allowed: true,
+                rule: None,
+            };
+        }
+
+        let mut best: Option<&Rule> = None;
+        for rule in self
+            .groups_for(user_agent)
+            .into_iter()
+            .flat_map(|group| &group.rules)
+        {
+            if !pattern_matches(&rule.pattern, &path) {
+                continue;
+            }
+            let better = match best {
+                None => true,
+                Some(current) => {
+                    rule.pattern.len() > current.pattern.len()
+                        || (rule.pattern.len() == current.pattern.len()
+                            && rule.allow
+                            && !current.allow)
+                }
+            };
+            if better {
+                best = Some(rule);
+            }
+        }
+
+        Verdict {
+            allowed: best.is_none_or(|rule| rule.allow),
+            rule: best,
+        }
+    }
+
+    pub fn is_allowed(&self, path: &str, user_agent: &str) -> bool {
+        self.check(path, user_agent).allowed
+    }
+
+    /// The `Crawl-delay` of the first group for `user_agent` that sets one.
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<f64> {
+        self.groups_for(user_agent)
+            .into_iter()
+            .find_map(|group| group.crawl_delay)
+    }
+
+    /// The `Request-rate` of the first group for `user_agent` that sets one.
+    pub fn request_rate(&self, user_agent: &str) -> Option<RequestRate> {
+        self.groups_for(user_agent)
+            .into_iter()
+            .find_map(|group| group.request_rate)
+    }
+
+    /// Seconds to wait between requests for `user_agent`: the longer of its
+    /// `Crawl-delay` and the interval its `Request-rate` implies.
+    pub fn min_interval(&self, user_agent: &str) -> Option<f64> {
+        let crawl_delay = self.crawl_delay(user_agent);
+        let rate_interval = self.request_rate(user_agent).map(|rate| rate.interval());
+        match (crawl_delay, rate_interval) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// A problem found by [`lint`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    /// 1-based line in the robots.txt file
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Check a robots.txt body for lines [`RobotsTxt::parse`] would skip or
+/// read differently from what the author probably meant.
+pub fn lint(content: &str) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |line: usize, message: String| issues.push(LintIssue { line, message });
+
+    let prepared = prepare(content);
+    let mut in_group = false;
+
+    for (index, raw_line) in prepared.lines().enumerate() {
+        let line = index + 1;
+        let text = strip_comment(raw_line);
+        if text.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = text.split_once(':') else {
+            issue(line, format!("no `:` after the field name in \"{}\"", text));
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+
+        match key.as_str() {
+            "user-agent" => {
+                in_group = true;
+                let token = agent_token(value);
+                if token.is_empty() {
+                    issue(line, "user-agent has no product token".to_string());
+                } else if token != "*" && !value.eq_ignore_ascii_case(&token) {
+                    issue(
+                        line,
+                        format!(
+                            "user-agent \"{}\" is matched by its product token `{}` only",
+                            value, token
+                        ),
+                    );
+                }
+            }
+            "allow" | "disallow" | "crawl-delay" | "request-rate" if !in_group => {
+                issue(
+                    line,
+                    format!("{} before any user-agent line is ignored", key),
+                );
+            }
+            "allow" | "disallow" => {
+                if !value.is_empty() && !value.starts_with('/') && !value.starts_with('*') {
+                    issue(line, format!("pattern \"{}\" should start with `/`", value));
+                }
+                if value.find('$').is_some_and(|i| i + 1 < value.len()) {
+                    issue(
+                        line,
+                        format!(
+                            "`$` in \"{}\" is only an anchor at the end of a pattern",
+                            value
+                        ),
+                    );
+                }
+            }
+            "crawl-delay" => {
+                let valid = value
+                    .parse::<f64>()
+                    .is_ok_and(|delay| delay.is_finite() && delay >= 0.0);
+                if !valid {
+                    issue(line, format!("invalid crawl-delay \"{}\"", value));
+                }
+            }
+            "request-rate" => {
+                if RequestRate::parse(value).is_none() {
+                    issue(line, format!("invalid request-rate \"{}\"", value));
+                }
+            }
+            "sitemap" => {
+                let absolute = url::Url::parse(value)
+                    .is_ok_and(|url| url.scheme() == "http" || url.scheme() == "https");
+                if !absolute {
+                    issue(
+                        line,
+                        format!("sitemap \"{}\" is not an absolute URL", value),
+                    );
+                }
+            }
+            _ => issue(line, format!("unknown field `{}`", key)),
+        }
+    }
+
+    if content.len() > MAX_ROBOTS_BYTES {
+        let line = prepared.lines().count();
+        issue(
+            line,
+            format!(
+                "file is {} bytes; only the first {} are read",
+                content.len(),
+                MAX_ROBOTS_BYTES
+            ),
+        );
+    }
+
+    issues
+}
+
+/// The product token a user agent is matched by: the leading run of
+/// letters, `-` and `_`, lowercased. `RustCrawler/1.0 (+url)` gives
+/// `rustcrawler`.
+pub fn agent_token(user_agent: &str) -> String {
+    let user_agent = user_agent.trim();
+    if user_agent.starts_with('*') {
+        return "*".to_string();
+    }
+
+    user_agent
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic() || *c == '-' || *c == '_')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+// The part of a body that is parsed, with every line ending turned into LF
+fn prepare(content: &str) -> String {
+    let content = truncate(content, MAX_ROBOTS_BYTES);
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    // CR, LF and CRLF all end a line
+    content.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.split('#').next().unwrap_or("").trim()
+}
+
+fn normalize_path(path: &str) -> String {
+    let path = normalize_encoding(path);
+    if path.starts_with('/') {
+        path
+    } else {
+        format!("/{}", path)
+    }
+}
+
+// Patterns and paths are compared in one canonical percent-encoding:
+// non-ASCII bytes encoded, escapes of unreserved characters decoded, and hex
+// digits uppercased. `*` and `$` are kept as they are.
+fn normalize_encoding(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'%' && i + 2 < bytes.len() {
+            if let Some(decoded) = hex_pair(bytes[i + 1], bytes[i + 2]) {
+                if is_unreserved(decoded) {
+                    out.push(decoded as char);
+                } else {
+                    out.push_str(&format!("%{:02X}", decoded));
+                }
+                i += 3;
+                continue;
+            }
+        }
+
+        if b.is_ascii() {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+        i += 1;
+    }
+
+    out
+}
+
+fn hex_pair(high: u8, low: u8) -> Option<u8> {
+    let digit = |c: u8| (c as char).to_digit(16);
+    Some((digit(high)? * 16 + digit(low)?) as u8)
+}
+
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
+
+// `*` matches any run of characters and a trailing `$` anchors the pattern
+// to the end of the path; otherwise patterns match as prefixes
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let path = path.as_bytes();
+    let pattern = pattern.as_bytes();
+
+    // Every position in the path the pattern so far can end at
+    let mut ends = vec![0];
+    for (i, &c) in pattern.iter().enumerate() {
+        if c == b'$' && i + 1 == pattern.len() {
+            return ends.contains(&path.len());
+        }
+
+        if c == b'*' {
+            ends = (ends[0]..=path.len()).collect();
+        } else {
+            ends = ends
+                .into_iter()
+                .filter(|&end| end < path.len() && path[end] == c)
+                .map(|end| end + 1)
+                .collect();
+            if ends.is_empty() {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn truncate(content: &str, max_bytes: usize) -> &str {
+    if content.len() <= max_bytes {
+        return content;
+    }
+
+    let mut end = max_bytes;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    &content[..end]
+}
@@ -0,0 +1,92 @@
+// src/seed.rs
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::{CrawlerError, Result};
+
+/// A start URL, optionally with its own depth limit and a tag that is carried
+/// onto every page discovered from it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Seed {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl Seed {
+    pub fn new<S: Into<String>>(url: S) -> Self {
+        Self {
+            url: url.into(),
+            max_depth: None,
+            tag: None,
+        }
+    }
+
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+}
+
+/// Parses one seed-file line: a URL followed by optional `depth=N` and
+/// `tag=NAME` fields, separated by whitespace.
+impl FromStr for Seed {
+    type Err = CrawlerError;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let mut fields = line.split_whitespace();
+        let url = fields
+            .next()
+            .ok_or_else(|| CrawlerError::ConfigError("Empty seed line".to_string()))?;
+        url::Url::parse(url)?;
+
+        let mut seed = Seed::new(url);
+        for field in fields {
+            match field.split_once('=') {
+                Some(("depth", value)) => {
+                    let depth = value.parse().map_err(|_| {
+                        CrawlerError::ConfigError(format!("Invalid seed depth: {}", value))
+                    })?;
+                    seed.max_depth = Some(depth);
+                }
+                Some(("tag", value)) => seed.tag = Some(value.to_string()),
+                _ => {
+                    return Err(CrawlerError::ConfigError(format!(
+                        "Unknown seed field: {}",
+                        field
+                    )))
+                }
+            }
+        }
+
+        Ok(seed)
+    }
+}
+
+/// Loads seeds from a newline-delimited file. Blank lines and lines starting
+/// with `#` are ignored.
+pub fn load_seeds_from_file<P: AsRef<Path>>(path: P) -> Result<Vec<Seed>> {
+    let content = fs::read_to_string(path.as_ref())
+        .map_err(|e| CrawlerError::ConfigError(format!("Failed to read seed file: {}", e)))?;
+
+    content
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            line.parse().map_err(|e| {
+                CrawlerError::ConfigError(format!("Seed file line {}: {}", number + 1, e))
+            })
+        })
+        .collect()
+}
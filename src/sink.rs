@@ -0,0 +1,91 @@
+// src/sink.rs
+use std::path::PathBuf;
+
+use crate::crawler::CrawlResult;
+use crate::error::Result;
+use crate::storage;
+use crate::visualization::GraphVisualizer;
+
+/// A destination for a finished crawl.
+///
+/// Sinks registered on a [`CrawlerBuilder`](crate::CrawlerBuilder) are written
+/// in registration order once `Crawler::crawl` has built its result.
+pub trait OutputSink: Send + Sync {
+    fn write(&self, result: &CrawlResult) -> Result<()>;
+}
+
+/// Writes the result with [`storage::save_results`] (`.json`, `.yaml`, `.yml`).
+#[derive(Debug, Clone)]
+pub struct JsonFileSink {
+    path: PathBuf,
+}
+
+impl JsonFileSink {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl OutputSink for JsonFileSink {
+    fn write(&self, result: &CrawlResult) -> Result<()> {
+        storage::save_results(result, &self.path)
+    }
+}
+
+/// Exports the link graph in Graphviz DOT format.
+#[derive(Debug, Clone)]
+pub struct DotFileSink {
+    path: String,
+}
+
+impl DotFileSink {
+    pub fn new<P: Into<String>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl OutputSink for DotFileSink {
+    fn write(&self, result: &CrawlResult) -> Result<()> {
+        let mut visualizer = GraphVisualizer::new();
+        visualizer.build_from_crawler_graph(&result.graph);
+        visualizer.flag_nofollow(&result.nofollow_edges);
+        visualizer.export_dot(&self.path)
+    }
+}
+
+/// Exports the interactive D3 visualization.
+#[derive(Debug, Clone)]
+pub struct HtmlFileSink {
+    path: String,
+    max_nodes: usize,
+    max_links_per_node: usize,
+}
+
+impl HtmlFileSink {
+    pub fn new<P: Into<String>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            max_nodes: 300,
+            max_links_per_node: 10,
+        }
+    }
+
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    pub fn with_max_links_per_node(mut self, max_links_per_node: usize) -> Self {
+        self.max_links_per_node = max_links_per_node;
+        self
+    }
+}
+
+impl OutputSink for HtmlFileSink {
+    fn write(&self, result: &CrawlResult) -> Result<()> {
+        let mut visualizer = GraphVisualizer::new();
+        visualizer.build_from_crawler_graph(&result.graph);
+        visualizer.flag_nofollow(&result.nofollow_edges);
+        visualizer.export_html_optimized(&self.path, self.max_nodes, self.max_links_per_node)
+    }
+}
@@ -0,0 +1,399 @@
+// src/sitemap.rs
+use flate2::read::GzDecoder;
+use log::{debug, warn};
+use quick_xml::events::Event;
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::reader::NsReader;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Read;
+use url::Url;
+
+use crate::error::{CrawlerError, Result};
+use crate::robots::RobotsChecker;
+
+// How deep sitemap indexes may nest before we stop following them
+const MAX_INDEX_DEPTH: usize = 3;
+// The protocol's limit on a sitemap's uncompressed size
+const MAX_SITEMAP_SIZE: u64 = 50 * 1024 * 1024;
+const SITEMAP_NAMESPACE: &[u8] = b"http://www.sitemaps.org/schemas/sitemap/0.9";
+
+/// A `<url>` entry from a urlset sitemap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SitemapEntry {
+    pub loc: String,
+    pub lastmod: Option<String>,
+    pub priority: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    /// A `<urlset>` listing pages
+    UrlSet(Vec<SitemapEntry>),
+    /// A `<sitemapindex>` listing further sitemap URLs
+    Index(Vec<String>),
+}
+
+/// Parses a urlset or sitemapindex document. Gzipped input is detected by its
+/// magic bytes and decompressed first, whatever the URL or content type says.
+///
+/// Only `<loc>`, `<lastmod>` and `<priority>` elements that are direct
+/// children of a `<url>` or `<sitemap>` are read, so extensions such as
+/// `<image:loc>` don't overwrite the page's own fields.
+pub fn parse_sitemap(bytes: &[u8]) -> Result<Sitemap> {
+    let decompressed;
+    let xml = if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut buf = Vec::new();
+        GzDecoder::new(bytes)
+            .take(MAX_SITEMAP_SIZE + 1)
+            .read_to_end(&mut buf)
+            .map_err(|e| {
+                CrawlerError::SitemapError(format!("Failed to decompress sitemap: {}", e))
+            })?;
+        if buf.len() as u64 > MAX_SITEMAP_SIZE {
+            return Err(CrawlerError::SitemapError(format!(
+                "Sitemap is larger than {} bytes uncompressed",
+                MAX_SITEMAP_SIZE
+            )));
+        }
+        decompressed = buf;
+        &decompressed[..]
+    } else {
+        bytes
+    };
+
+    let mut reader = NsReader::from_reader(xml);
+    reader.trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut is_index = None;
+    let mut entries = Vec::new();
+    let mut sitemaps = Vec::new();
+    let mut current: Option<SitemapEntry> = None;
+    let mut field: Option<Vec<u8>> = None;
+    // Element nesting, and the level of the open `<url>`/`<sitemap>`
+    let mut depth = 0;
+    let mut entry_depth = None;
+
+    loop {
+        match reader.read_resolved_event_into(&mut buf) {
+            Ok((ns, Event::Start(e))) => {
+                depth += 1;
+                let name = e.local_name().as_ref().to_vec();
+                match name.as_slice() {
+                    _ if !in_sitemap_namespace(&ns) => {}
+                    b"urlset" => is_index = Some(false),
+                    b"sitemapindex" => is_index = Some(true),
+                    b"url" | b"sitemap" => {
+                        current = Some(SitemapEntry {
+                            loc: String::new(),
+                            lastmod: None,
+                            priority: None,
+                        });
+                        entry_depth = Some(depth);
+                    }
+                    _ if entry_depth.map(|d| d + 1) == Some(depth) => field = Some(name),
+                    _ => {}
+                }
+            }
+            Ok((_, Event::Text(e))) => {
+                let text = e.unescape().map_err(|e| {
+                    CrawlerError::SitemapError(format!("Invalid sitemap text: {}", e))
+                })?;
+                set_field(&mut current, field.as_deref(), text.trim());
+            }
+            Ok((_, Event::CData(e))) => {
+                let text = String::from_utf8_lossy(&e);
+                set_field(&mut current, field.as_deref(), text.trim());
+            }
+            Ok((ns, Event::End(e))) => {
+                depth -= 1;
+                field = None;
+                let closes_entry = in_sitemap_namespace(&ns) && entry_depth == Some(depth + 1);
+                match e.local_name().as_ref() {
+                    _ if !closes_entry => {}
+                    b"url" => {
+                        entry_depth = None;
+                        if let Some(entry) = current.take().filter(|e| !e.loc.is_empty()) {
+                            entries.push(entry);
+                        }
+                    }
+                    b"sitemap" => {
+                        entry_depth = None;
+                        if let Some(entry) = current.take().filter(|e| !e.loc.is_empty()) {
+                            sitemaps.push(entry.loc);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok((_, Event::Eof)) => break,
+            Ok(_) => {}
+            Err(e) => {
+                return Err(CrawlerError::SitemapError(format!(
+                    "Failed to parse sitemap XML at position {}: {}",
+                    reader.buffer_position(),
+                    e
+                )))
+            }
+        }
+        buf.clear();
+    }
+
+    match is_index {
+        Some(true) => Ok(Sitemap::Index(sitemaps)),
+        Some(false) => Ok(Sitemap::UrlSet(entries)),
+        None => Err(CrawlerError::SitemapError(
+            "Document is neither a urlset nor a sitemapindex".to_string(),
+        )),
+    }
+}
+
+// Elements of the sitemap protocol. Sitemaps that declare no namespace at
+// all are still accepted
+fn in_sitemap_namespace(ns: &ResolveResult) -> bool {
+    match ns {
+        ResolveResult::Bound(Namespace(ns)) => *ns == SITEMAP_NAMESPACE,
+        ResolveResult::Unbound => true,
+        ResolveResult::Unknown(_) => false,
+    }
+}
+
+fn set_field(current: &mut Option<SitemapEntry>, field: Option<&[u8]>, text: &str) {
+    let Some(entry) = current.as_mut() else {
+        return;
+    };
+
+    match field {
+        Some(b"loc") => entry.loc = text.to_string(),
+        Some(b"lastmod") => entry.lastmod = Some(text.to_string()),
+        Some(b"priority") => entry.priority = text.parse().ok(),
+        _ => {}
+    }
+}
+
+/// Finds and reads the sitemaps for one site.
+pub struct SitemapFetcher<'a> {
+    client: &'a Client,
+    robots_checker: &'a RobotsChecker,
+    max_urls: Option<usize>,
+}
+
+impl<'a> SitemapFetcher<'a> {
+    pub fn new(
+        client: &'a Client,
+        robots_checker: &'a RobotsChecker,
+        max_urls: Option<usize>,
+    ) -> Self {
+        Self {
+            client,
+            robots_checker,
+            max_urls,
+        }
+    }
+
+    /// Collects page entries from every sitemap advertised in the site's
+    /// robots.txt, falling back to `/sitemap.xml` when it lists none. Nested
+    /// sitemap indexes are followed up to a fixed depth.
+    pub async fn discover(&self, site_url: &str) -> Result<Vec<SitemapEntry>> {
+        let base = Url::parse(site_url)?;
+
+        let mut to_fetch = self.robots_checker.sitemaps(site_url).await?;
+        if to_fetch.is_empty() {
+            to_fetch.push(base.join("/sitemap.xml")?.to_string());
+        }
+
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        let mut pending: Vec<(String, usize)> = to_fetch.into_iter().map(|u| (u, 0)).collect();
+
+        while let Some((sitemap_url, depth)) = pending.pop() {
+            if !seen.insert(sitemap_url.clone()) {
+                continue;
+            }
+
+            let sitemap = match self.fetch(&sitemap_url).await {
+                Ok(sitemap) => sitemap,
+                Err(e) => {
+                    warn!("⚠️  Skipping sitemap {}: {}", sitemap_url, e);
+                    continue;
+                }
+            };
+
+            match sitemap {
+                Sitemap::UrlSet(urls) => {
+                    debug!("🗺️  {} lists {} URLs", sitemap_url, urls.len());
+                    entries.extend(urls);
+                }
+                Sitemap::Index(children) if depth < MAX_INDEX_DEPTH => {
+                    debug!("🗺️  {} lists {} sitemaps", sitemap_url, children.len());
+                    pending.extend(children.into_iter().map(|u| (u, depth + 1)));
+                }
+                Sitemap::Index(_) => {
+                    warn!("⚠️  Sitemap index nested too deeply: {}", sitemap_url);
+                }
+            }
+
+            if let Some(max_urls) = self.max_urls {
+                if entries.len() >= max_urls {
+                    entries.truncate(max_urls);
+                    break;
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    async fn fetch(&self, sitemap_url: &str) -> Result<Sitemap> {
+        let response = self.client.get(sitemap_url).send().await?;
+        if !response.status().is_success() {
+            return Err(CrawlerError::SitemapError(format!(
+                "status {}",
+                response.status()
+            )));
+        }
+
+        let bytes = response.bytes().await?;
+        parse_sitemap(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn urls(xml: &str) -> Vec<SitemapEntry> {
+        match parse_sitemap(xml.as_bytes()).unwrap() {
+            Sitemap::UrlSet(entries) => entries,
+            other => panic!("expected a urlset, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reads_urlset_entries() {
+        let entries = urls(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <url><loc>https://a.com/</loc><lastmod>2024-01-01</lastmod><priority>0.8</priority></url>
+              <url><loc><![CDATA[https://a.com/b?x=1&y=2]]></loc></url>
+              <url><lastmod>2024-01-01</lastmod></url>
+            </urlset>"#,
+        );
+
+        assert_eq!(
+            entries,
+            vec![
+                SitemapEntry {
+                    loc: "https://a.com/".to_string(),
+                    lastmod: Some("2024-01-01".to_string()),
+                    priority: Some(0.8),
+                },
+                SitemapEntry {
+                    loc: "https://a.com/b?x=1&y=2".to_string(),
+                    lastmod: None,
+                    priority: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_sitemap_index() {
+        let sitemap = parse_sitemap(
+            br#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <sitemap><loc>https://a.com/s1.xml</loc></sitemap>
+              <sitemap><loc>https://a.com/s2.xml</loc></sitemap>
+            </sitemapindex>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            sitemap,
+            Sitemap::Index(vec![
+                "https://a.com/s1.xml".to_string(),
+                "https://a.com/s2.xml".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn extension_elements_dont_overwrite_fields() {
+        let entries = urls(
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+                      xmlns:image="http://www.google.com/schemas/sitemap-image/1.1"
+                      xmlns:news="http://www.google.com/schemas/sitemap-news/0.9">
+              <url>
+                <loc>https://a.com/page</loc>
+                <image:image><image:loc>https://a.com/photo.jpg</image:loc></image:image>
+                <news:news><news:publication_date>2024-02-02</news:publication_date></news:news>
+                <image:loc>https://a.com/other.jpg</image:loc>
+              </url>
+            </urlset>"#,
+        );
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].loc, "https://a.com/page");
+        assert_eq!(entries[0].lastmod, None);
+    }
+
+    #[test]
+    fn only_direct_children_are_fields() {
+        let entries = urls(
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <url>
+                <loc>https://a.com/page</loc>
+                <extra><loc>https://a.com/nested</loc></extra>
+              </url>
+            </urlset>"#,
+        );
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].loc, "https://a.com/page");
+    }
+
+    #[test]
+    fn accepts_sitemaps_without_a_namespace() {
+        let entries = urls("<urlset><url><loc>https://a.com/</loc></url></urlset>");
+        assert_eq!(entries.len(), 1);
+
+        // A foreign namespace is not the sitemap protocol
+        let foreign = r#"<urlset xmlns="https://example.com/other"><url><loc>https://a.com/</loc></url></urlset>"#;
+        assert!(parse_sitemap(foreign.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(parse_sitemap(b"<html><body>Not found</body></html>").is_err());
+        assert!(parse_sitemap(b"<urlset><url><loc>x</url>").is_err());
+    }
+
+    #[test]
+    fn decompresses_gzipped_sitemaps() {
+        let xml = "<urlset><url><loc>https://a.com/</loc></url></urlset>";
+        let sitemap = parse_sitemap(&gzip(xml.as_bytes())).unwrap();
+        assert_eq!(sitemap, parse_sitemap(xml.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn caps_gzipped_sitemap_size() {
+        let at_limit = vec![b' '; MAX_SITEMAP_SIZE as usize];
+        let err = parse_sitemap(&gzip(&at_limit)).unwrap_err();
+        // Within the limit, the (blank) document is read and found wanting
+        assert!(!err.to_string().contains("larger than"), "{}", err);
+
+        let over_limit = vec![b' '; MAX_SITEMAP_SIZE as usize + 1];
+        let err = parse_sitemap(&gzip(&over_limit)).unwrap_err();
+        assert!(err.to_string().contains("larger than"), "{}", err);
+    }
+}
@@ -5,6 +5,8 @@ use std::path::Path;
 
 use crate::crawler::CrawlResult;
 use crate::error::{CrawlerError, Result};
+use crate::fingerprint::DuplicateCluster;
+use crate::page::RedirectHop;
 
 #[derive(Serialize, Deserialize)]
 pub struct StoredCrawlResult {
@@ -13,11 +15,24 @@ pub struct StoredCrawlResult {
     pub crawl_duration_seconds: f64,
     pub success_count: usize,
     pub error_count: usize,
+    /// The crawl was interrupted before it finished
+    #[serde(default)]
+    pub partial: bool,
     pub pages: Vec<StoredPage>,
     pub graph: std::collections::HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub canonical_groups: std::collections::HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub duplicate_clusters: Vec<DuplicateCluster>,
+    /// Source URL -> linked URLs marked nofollow (also in `graph`)
+    #[serde(default)]
+    pub nofollow_edges: std::collections::HashMap<String, Vec<String>>,
+    /// URLs left unfetched because robots.txt disallows them
+    #[serde(default)]
+    pub robots_disallowed: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredPage {
     pub url: String,
     pub title: Option<String>,
@@ -27,6 +42,43 @@ pub struct StoredPage {
     pub size_bytes: Option<usize>,
     pub links_count: usize,
     pub crawled_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default)]
+    pub seed: Option<String>,
+    #[serde(default)]
+    pub seed_tag: Option<String>,
+    #[serde(default)]
+    pub sitemap_lastmod: Option<String>,
+    #[serde(default)]
+    pub sitemap_priority: Option<f32>,
+    #[serde(default)]
+    pub redirects: Vec<RedirectHop>,
+    #[serde(default)]
+    pub final_url: Option<String>,
+    #[serde(default)]
+    pub canonical: Option<String>,
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub simhash: Option<u64>,
+    #[serde(default)]
+    pub duplicate_of: Option<String>,
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    #[serde(default)]
+    pub not_modified: bool,
+    /// Outgoing links, reused when the page is revalidated with a 304
+    #[serde(default)]
+    pub links: Vec<String>,
+    #[serde(default)]
+    pub parse_time_ms: Option<f64>,
+    #[serde(default)]
+    pub robots_directives: Vec<String>,
+    #[serde(default)]
+    pub nofollow_links: Vec<String>,
 }
 
 pub fn save_results<P: AsRef<Path>>(result: &CrawlResult, path: P) -> Result<()> {
@@ -43,6 +95,7 @@ pub fn save_results<P: AsRef<Path>>(result: &CrawlResult, path: P) -> Result<()>
         crawl_duration_seconds: result.stats.duration_secs,
         success_count: result.stats.success_count,
         error_count: result.stats.error_count,
+        partial: result.partial,
         pages: result
             .pages
             .iter()
@@ -55,9 +108,31 @@ pub fn save_results<P: AsRef<Path>>(result: &CrawlResult, path: P) -> Result<()>
                 size_bytes: page.size,
                 links_count: page.links.len(),
                 crawled_at: page.crawled_at,
+                attempts: page.attempts,
+                seed: page.seed.clone(),
+                seed_tag: page.seed_tag.clone(),
+                sitemap_lastmod: page.sitemap_lastmod.clone(),
+                sitemap_priority: page.sitemap_priority,
+                redirects: page.redirects.clone(),
+                final_url: page.final_url.clone(),
+                canonical: page.canonical.clone(),
+                content_hash: page.content_hash.clone(),
+                simhash: page.simhash,
+                duplicate_of: page.duplicate_of.clone(),
+                etag: page.etag.clone(),
+                last_modified: page.last_modified.clone(),
+                not_modified: page.not_modified,
+                links: page.links.clone(),
+                parse_time_ms: page.parse_time_ms,
+                robots_directives: page.robots_directives.clone(),
+                nofollow_links: page.nofollow_links.clone(),
             })
             .collect(),
         graph: result.graph.clone(),
+        canonical_groups: result.canonical_groups.clone(),
+        duplicate_clusters: result.duplicate_clusters.clone(),
+        nofollow_edges: result.nofollow_edges.clone(),
+        robots_disallowed: result.robots_disallowed.clone(),
     };
 
     let file = File::create(path.as_ref())
@@ -82,8 +157,6 @@ pub fn save_results<P: AsRef<Path>>(result: &CrawlResult, path: P) -> Result<()>
     Ok(())
 }
 
-// Add this line above the function
-#[allow(dead_code)]
 pub fn load_results<P: AsRef<Path>>(path: P) -> Result<StoredCrawlResult> {
     let extension = path
         .as_ref()
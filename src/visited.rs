@@ -0,0 +1,520 @@
+// src/visited.rs
+use dashmap::DashSet;
+use growable_bloom_filter::GrowableBloom;
+use log::error;
+use redb::{Database, Durability, ReadableTableMetadata, TableDefinition};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
+
+use crate::error::{CrawlerError, Result};
+
+const VISITED_TABLE: TableDefinition<&str, ()> = TableDefinition::new("visited");
+
+/// The set of URLs the crawler has already seen (queued or fetched).
+///
+/// Shared by all workers without an outer lock, so implementations handle
+/// concurrent calls themselves.
+pub trait VisitedStore: Send + Sync {
+    /// Mark a URL as seen. Returns `true` if it had not been seen before.
+    fn insert(&self, url: &str) -> bool;
+
+    /// Mark several URLs as seen, in order. Returns whether each was new.
+    fn insert_all(&self, urls: &[String]) -> Vec<bool> {
+        urls.iter().map(|url| self.insert(url)).collect()
+    }
+
+    /// Whether inserts may wait on I/O. The crawler then makes them from
+    /// the blocking thread pool rather than its async workers.
+    fn is_blocking(&self) -> bool {
+        false
+    }
+
+    fn contains(&self, url: &str) -> bool;
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every URL in the store, or `None` if the store can't list them (a
+    /// Bloom filter only answers membership queries).
+    fn urls(&self) -> Option<Vec<String>>;
+
+    /// Whether the URLs outlive the crawl in a store that a resumed crawl
+    /// reopens, so checkpoints needn't list them.
+    fn persists(&self) -> bool {
+        false
+    }
+}
+
+/// Which [`VisitedStore`] the crawler uses, selectable from the config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VisitedStoreConfig {
+    /// Every URL kept in a hash set in memory
+    #[default]
+    Memory,
+    /// A Bloom filter that grows as URLs are added. Uses a few bytes per
+    /// URL, but a small share of new URLs is wrongly taken as seen and
+    /// skipped.
+    Bloom {
+        #[serde(default = "default_false_positive_rate")]
+        false_positive_rate: f64,
+        /// Initial capacity; the filter grows beyond it as needed
+        #[serde(default = "default_expected_urls")]
+        expected_urls: usize,
+    },
+    /// URLs kept in a database file on disk. An existing file is emptied
+    /// first unless `reuse` is set (a resumed crawl sets it), in which case
+    /// the crawl continues with the URLs already in it.
+    Disk {
+        path: String,
+        #[serde(default)]
+        reuse: bool,
+    },
+}
+
+fn default_false_positive_rate() -> f64 {
+    0.001
+}
+
+fn default_expected_urls() -> usize {
+    1_000_000
+}
+
+impl VisitedStoreConfig {
+    pub fn build(&self) -> Result<Box<dyn VisitedStore>> {
+        Ok(match self {
+            VisitedStoreConfig::Memory => Box::new(MemoryVisitedStore::new()),
+            VisitedStoreConfig::Bloom {
+                false_positive_rate,
+                expected_urls,
+            } => Box::new(BloomVisitedStore::new(*false_positive_rate, *expected_urls)),
+            VisitedStoreConfig::Disk { path, reuse } => {
+                Box::new(DiskVisitedStore::open(path, *reuse)?)
+            }
+        })
+    }
+}
+
+/// Every URL in a sharded hash set, so workers inserting at the same time
+/// rarely contend.
+#[derive(Debug, Default)]
+pub struct MemoryVisitedStore {
+    urls: DashSet<String>,
+}
+
+impl MemoryVisitedStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl VisitedStore for MemoryVisitedStore {
+    fn insert(&self, url: &str) -> bool {
+        // Avoid allocating for the common already-seen case
+        !self.urls.contains(url) && self.urls.insert(url.to_string())
+    }
+
+    fn contains(&self, url: &str) -> bool {
+        self.urls.contains(url)
+    }
+
+    fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    fn urls(&self) -> Option<Vec<String>> {
+        Some(self.urls.iter().map(|url| url.clone()).collect())
+    }
+}
+
+/// A scalable Bloom filter: new filter layers are added as it fills up, so
+/// the false-positive rate holds however many URLs are inserted.
+#[derive(Debug)]
+pub struct BloomVisitedStore {
+    filter: Mutex<GrowableBloom>,
+}
+
+impl BloomVisitedStore {
+    pub fn new(false_positive_rate: f64, expected_urls: usize) -> Self {
+        Self {
+            filter: Mutex::new(GrowableBloom::new(
+                false_positive_rate,
+                expected_urls.max(1),
+            )),
+        }
+    }
+}
+
+impl VisitedStore for BloomVisitedStore {
+    // Lookups are a handful of hash probes, so a plain mutex is enough
+    fn insert(&self, url: &str) -> bool {
+        self.filter.lock().unwrap().insert(url)
+    }
+
+    fn contains(&self, url: &str) -> bool {
+        self.filter.lock().unwrap().contains(url)
+    }
+
+    fn len(&self) -> usize {
+        self.filter.lock().unwrap().len()
+    }
+
+    fn urls(&self) -> Option<Vec<String>> {
+        None
+    }
+}
+
+/// URLs stored in a redb database file. Commits aren't synced to disk one
+/// by one; the file is a place to keep the set out of memory, not a
+/// durable record.
+///
+/// New URLs collect in a small in-memory batch and are written to the file
+/// in one transaction once [`DISK_BATCH_SIZE`] have piled up, so a crawl
+/// doesn't pay for a write transaction per link. A URL is only dropped from
+/// the batch after its write is committed, so every URL is always either in
+/// the batch or in the file.
+pub struct DiskVisitedStore {
+    db: Database,
+    batch: DashSet<String>,
+    // Held shared while URLs are claimed in the batch and checked against
+    // the file, and exclusively while a flush picks the URLs to write, so
+    // it never writes a claim that is still being checked
+    claiming: RwLock<()>,
+    // Held by whoever is writing the batch out
+    flushing: Mutex<()>,
+    len: AtomicUsize,
+}
+
+/// How many new URLs [`DiskVisitedStore`] keeps in memory before writing
+/// them out.
+pub const DISK_BATCH_SIZE: usize = 1024;
+
+impl DiskVisitedStore {
+    /// Open (or create) the store at `path`. With `reuse`, URLs already in
+    /// the file count as visited; otherwise they are cleared.
+    pub fn open<P: AsRef<Path>>(path: P, reuse: bool) -> Result<Self> {
+        let db = Database::create(path.as_ref()).map_err(storage_error)?;
+
+        // Make sure the table exists and count what's already in it
+        let txn = db.begin_write().map_err(storage_error)?;
+        if !reuse {
+            txn.delete_table(VISITED_TABLE).map_err(storage_error)?;
+        }
+        let len = {
+            let table = txn.open_table(VISITED_TABLE).map_err(storage_error)?;
+            table.len().map_err(storage_error)? as usize
+        };
+        txn.commit().map_err(storage_error)?;
+
+        Ok(Self {
+            db,
+            batch: DashSet::new(),
+            claiming: RwLock::new(()),
+            flushing: Mutex::new(()),
+            len: AtomicUsize::new(len),
+        })
+    }
+
+    // Of the URLs claimed in the batch (`new`), unclaim those already in
+    // the file
+    fn drop_stored(&self, urls: &[String], new: &mut [bool]) -> Result<()> {
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = txn.open_table(VISITED_TABLE).map_err(storage_error)?;
+        for (url, new) in urls.iter().zip(new.iter_mut()) {
+            if *new && table.get(url.as_str()).map_err(storage_error)?.is_some() {
+                self.batch.remove(url);
+                *new = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the batched URLs to the file.
+    fn try_flush(&self) -> Result<()> {
+        // Someone else is already writing the batch out
+        let Ok(_flushing) = self.flushing.try_lock() else {
+            return Ok(());
+        };
+
+        let urls: Vec<String> = {
+            let _claiming = self.claiming.write().unwrap();
+            self.batch.iter().map(|url| url.clone()).collect()
+        };
+        if urls.is_empty() {
+            return Ok(());
+        }
+
+        let mut txn = self.db.begin_write().map_err(storage_error)?;
+        txn.set_durability(Durability::Eventual);
+        {
+            let mut table = txn.open_table(VISITED_TABLE).map_err(storage_error)?;
+            for url in &urls {
+                table.insert(url.as_str(), ()).map_err(storage_error)?;
+            }
+        }
+        txn.commit().map_err(storage_error)?;
+
+        for url in &urls {
+            self.batch.remove(url);
+        }
+        Ok(())
+    }
+
+    fn try_contains_on_disk(&self, url: &str) -> Result<bool> {
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = txn.open_table(VISITED_TABLE).map_err(storage_error)?;
+        Ok(table.get(url).map_err(storage_error)?.is_some())
+    }
+
+    fn try_urls(&self) -> Result<Vec<String>> {
+        self.try_flush()?;
+
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = txn.open_table(VISITED_TABLE).map_err(storage_error)?;
+
+        let mut urls = Vec::with_capacity(self.len());
+        for entry in table.range::<&str>(..).map_err(storage_error)? {
+            let (url, _) = entry.map_err(storage_error)?;
+            urls.push(url.value().to_string());
+        }
+        // Anything added while we were reading
+        urls.extend(self.batch.iter().map(|url| url.clone()));
+        urls.sort_unstable();
+        urls.dedup();
+        Ok(urls)
+    }
+}
+
+impl VisitedStore for DiskVisitedStore {
+    fn insert(&self, url: &str) -> bool {
+        self.insert_all(&[url.to_string()])[0]
+    }
+
+    fn insert_all(&self, urls: &[String]) -> Vec<bool> {
+        // Claim each URL in the batch first, then look for the claimed ones
+        // on disk in a single read. A URL leaves the batch only once it is
+        // in the file, so one that is in neither is new
+        let new = {
+            let _claiming = self.claiming.read().unwrap();
+            let mut new: Vec<bool> = urls
+                .iter()
+                .map(|url| self.batch.insert(url.clone()))
+                .collect();
+
+            // A failing disk shouldn't lose pages: a URL that can't be
+            // looked up counts as new
+            if new.contains(&true) {
+                if let Err(e) = self.drop_stored(urls, &mut new) {
+                    error!("Visited store read failed: {}", e);
+                }
+            }
+            new
+        };
+
+        let added = new.iter().filter(|&&new| new).count();
+        self.len.fetch_add(added, Ordering::Relaxed);
+        if self.batch.len() >= DISK_BATCH_SIZE {
+            if let Err(e) = self.try_flush() {
+                error!("Visited store write failed: {}", e);
+            }
+        }
+        new
+    }
+
+    // Every new URL is looked up in the file, and every so often a batch
+    // is committed
+    fn is_blocking(&self) -> bool {
+        true
+    }
+
+    // Reopened with `reuse` on resume
+    fn persists(&self) -> bool {
+        true
+    }
+
+    fn contains(&self, url: &str) -> bool {
+        self.batch.contains(url)
+            || self.try_contains_on_disk(url).unwrap_or_else(|e| {
+                error!("Visited store read failed for {}: {}", url, e);
+                false
+            })
+    }
+
+    fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    fn urls(&self) -> Option<Vec<String>> {
+        self.try_urls()
+            .map_err(|e| error!("Visited store read failed: {}", e))
+            .ok()
+    }
+}
+
+// Leave every URL in the file, so a resumed crawl can reuse it
+impl Drop for DiskVisitedStore {
+    fn drop(&mut self) {
+        if let Err(e) = self.try_flush() {
+            error!("Visited store write failed: {}", e);
+        }
+    }
+}
+
+fn storage_error<E: std::fmt::Display>(e: E) -> CrawlerError {
+    CrawlerError::StorageError(format!("Visited store: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    // A store file of the test's own, removed when dropped
+    struct TempStore(PathBuf);
+
+    impl TempStore {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("visited_{}_{}.redb", std::process::id(), name));
+            let _ = std::fs::remove_file(&path);
+            Self(path)
+        }
+
+        fn open(&self, reuse: bool) -> DiskVisitedStore {
+            DiskVisitedStore::open(&self.0, reuse).unwrap()
+        }
+    }
+
+    impl Drop for TempStore {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn urls(range: std::ops::Range<usize>) -> Vec<String> {
+        range.map(|i| format!("https://a.com/{}", i)).collect()
+    }
+
+    #[test]
+    fn memory_and_bloom_stores_report_new_urls() {
+        let stores: [Box<dyn VisitedStore>; 2] = [
+            Box::new(MemoryVisitedStore::new()),
+            Box::new(BloomVisitedStore::new(0.001, 100)),
+        ];
+        for store in stores {
+            assert!(store.insert("https://a.com/"));
+            assert!(!store.insert("https://a.com/"));
+            assert!(store.contains("https://a.com/"));
+            assert!(!store.contains("https://a.com/x"));
+            assert_eq!(store.len(), 1);
+        }
+    }
+
+    #[test]
+    fn disk_store_reports_new_urls() {
+        let temp = TempStore::new("new");
+        let store = temp.open(false);
+
+        assert!(store.insert("https://a.com/"));
+        assert!(!store.insert("https://a.com/"));
+        assert!(store.contains("https://a.com/"));
+        assert!(!store.contains("https://a.com/x"));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn repeated_urls_in_one_call_are_new_once() {
+        let temp = TempStore::new("repeated");
+        let store = temp.open(false);
+
+        let list = vec![
+            "https://a.com/x".to_string(),
+            "https://a.com/y".to_string(),
+            "https://a.com/x".to_string(),
+        ];
+        assert_eq!(store.insert_all(&list), vec![true, true, false]);
+        assert_eq!(store.insert_all(&list), vec![false, false, false]);
+        assert_eq!(store.len(), 2);
+    }
+
+    // URLs written out in a batch are still seen, and listed, afterwards
+    #[test]
+    fn urls_stay_seen_across_batches() {
+        let temp = TempStore::new("batches");
+        let store = temp.open(false);
+        let all = urls(0..DISK_BATCH_SIZE * 2 + 10);
+
+        for chunk in all.chunks(100) {
+            assert!(store.insert_all(chunk).iter().all(|&new| new));
+        }
+        assert!(store.batch.len() < DISK_BATCH_SIZE);
+        assert!(store.insert_all(&all).iter().all(|&new| !new));
+        assert!(all.iter().all(|url| store.contains(url)));
+        assert_eq!(store.len(), all.len());
+
+        let mut expected = all.clone();
+        expected.sort_unstable();
+        assert_eq!(store.urls(), Some(expected));
+    }
+
+    // A fresh crawl mustn't take the last crawl's URLs (its seeds, say) as
+    // already visited
+    #[test]
+    fn reopening_without_reuse_starts_empty() {
+        let temp = TempStore::new("fresh");
+        let seeds = urls(0..3);
+        {
+            let store = temp.open(false);
+            store.insert_all(&seeds);
+        }
+
+        let store = temp.open(false);
+        assert!(store.is_empty());
+        assert_eq!(store.insert_all(&seeds), vec![true; 3]);
+    }
+
+    #[test]
+    fn reopening_with_reuse_keeps_urls() {
+        let temp = TempStore::new("reuse");
+        let seen = urls(0..3);
+        {
+            let store = temp.open(false);
+            store.insert_all(&seen);
+        }
+
+        let store = temp.open(true);
+        assert_eq!(store.len(), 3);
+        assert_eq!(store.insert_all(&seen), vec![false; 3]);
+        assert!(store.insert("https://a.com/new"));
+    }
+
+    // Threads racing on the same URLs, across several flushes, see each
+    // one as new exactly once
+    #[test]
+    fn concurrent_inserts_claim_each_url_once() {
+        let temp = TempStore::new("concurrent");
+        let store = Arc::new(temp.open(false));
+        let all = Arc::new(urls(0..DISK_BATCH_SIZE * 3));
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let store = Arc::clone(&store);
+                let all = Arc::clone(&all);
+                std::thread::spawn(move || {
+                    all.chunks(7)
+                        .map(|chunk| store.insert_all(chunk).iter().filter(|&&n| n).count())
+                        .sum::<usize>()
+                })
+            })
+            .collect();
+        let new: usize = threads.into_iter().map(|t| t.join().unwrap()).sum();
+
+        assert_eq!(new, all.len());
+        assert_eq!(store.len(), all.len());
+    }
+}
@@ -1,6 +1,7 @@
 // src/visualization.rs
 use petgraph::dot::{Config, Dot};
 use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::Write;
@@ -8,9 +9,10 @@ use url::Url;
 
 use crate::error::{CrawlerError, Result};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct GraphVisualizer {
-    graph: DiGraph<String, ()>,
+    /// Edge weights say whether the link is marked nofollow
+    graph: DiGraph<String, bool>,
     node_map: HashMap<String, NodeIndex>,
 }
 
@@ -37,7 +39,25 @@ impl GraphVisualizer {
             let source_idx = self.node_map[source];
             for target in targets {
                 if let Some(&target_idx) = self.node_map.get(target) {
-                    self.graph.add_edge(source_idx, target_idx, ());
+                    self.graph.add_edge(source_idx, target_idx, false);
+                }
+            }
+        }
+    }
+
+    /// Mark edges as nofollow links (source URL -> target URLs), so they are
+    /// drawn dashed.
+    pub fn flag_nofollow(&mut self, nofollow_edges: &HashMap<String, Vec<String>>) {
+        for (source, targets) in nofollow_edges {
+            let Some(&source_idx) = self.node_map.get(source) else {
+                continue;
+            };
+            for target in targets {
+                let Some(&target_idx) = self.node_map.get(target) else {
+                    continue;
+                };
+                if let Some(edge) = self.graph.find_edge(source_idx, target_idx) {
+                    self.graph[edge] = true;
                 }
             }
         }
@@ -65,7 +85,18 @@ impl GraphVisualizer {
     pub fn export_dot(&self, path: &str) -> Result<()> {
         let dot = format!(
             "{:?}",
-            Dot::with_config(&self.graph, &[Config::EdgeNoLabel])
+            Dot::with_attr_getters(
+                &self.graph,
+                &[Config::EdgeNoLabel],
+                &|_, edge| {
+                    if *edge.weight() {
+                        "style=dashed".to_string()
+                    } else {
+                        String::new()
+                    }
+                },
+                &|_, _| String::new(),
+            )
         );
 
         let mut file = File::create(path).map_err(|e| {
@@ -145,15 +176,17 @@ impl GraphVisualizer {
         for &(_, source_idx, _) in &selected_nodes {
             let mut link_count = 0;
 
-            for target_idx in self
+            for edge in self
                 .graph
-                .neighbors_directed(source_idx, petgraph::Direction::Outgoing)
+                .edges_directed(source_idx, petgraph::Direction::Outgoing)
             {
+                let target_idx = edge.target();
                 if selected_indices.contains(&target_idx) && link_count < max_links_per_node {
                     links.push(format!(
-                        r#"{{"source": {}, "target": {}}}"#,
+                        r#"{{"source": {}, "target": {}, "nofollow": {}}}"#,
                         source_idx.index(),
-                        target_idx.index()
+                        target_idx.index(),
+                        edge.weight()
                     ));
 
                     *links_per_node.entry(source_idx).or_insert(0) += 1;
@@ -463,6 +496,8 @@ impl GraphVisualizer {
                 const target = typeof link.target === 'object' ? link.target : simulation.nodes().find(n => n.id === link.target);
                 
                 if (source && target) {{
+                    // Links marked nofollow are drawn dashed
+                    context.setLineDash(link.nofollow ? [2, 2] : []);
                     context.moveTo(source.x, source.y);
                     context.lineTo(target.x, target.y);
                     context.stroke();
@@ -470,6 +505,7 @@ impl GraphVisualizer {
             }}
             
             // Draw nodes
+            context.setLineDash([]);
             context.globalAlpha = 1.0;
             
             for (const node of visibleNodes) {{
@@ -512,4 +548,3 @@ impl GraphVisualizer {
         Ok(())
     }
 }
-
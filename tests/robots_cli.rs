@@ -0,0 +1,197 @@
+// tests/robots_cli.rs
+//! Output of the `robots check` and `robots lint` subcommands against local
+//! robots.txt files and a local server.
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+// Write `content` to a robots.txt file of the test's own
+fn robots_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("robots_cli_{}_{}.txt", std::process::id(), name));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+// Serve `respond(path)` for every request, returning the site's base URL
+fn serve(respond: fn(&str) -> String) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for mut socket in listener.incoming().flatten() {
+            let mut buf = [0; 4096];
+            let n = socket.read(&mut buf).unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let path = request.split_whitespace().nth(1).unwrap_or("/");
+            let _ = socket.write_all(respond(path).as_bytes());
+        }
+    });
+    base
+}
+
+fn response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rust-crawler"))
+        .args(args)
+        .env("RUST_LOG", "off")
+        .output()
+        .expect("binary runs")
+}
+
+fn stdout_lines(output: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn check_names_the_deciding_rule_and_its_line() {
+    let path = robots_file(
+        "check",
+        "# site rules\nuser-agent: *\ndisallow: /private\n\nallow: /private/open\n",
+    );
+    let robots = path.to_str().unwrap();
+
+    let output = run(&[
+        "robots",
+        "check",
+        "--robots",
+        robots,
+        "/private/x",
+        "/private/open/y",
+        "https://example.com/public?q=1",
+    ]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout_lines(&output),
+        vec![
+            "/private/x: disallowed by \"Disallow: /private\" (line 3)",
+            "/private/open/y: allowed by \"Allow: /private/open\" (line 5)",
+            "https://example.com/public?q=1: allowed (no rule matches)",
+        ]
+    );
+}
+
+#[test]
+fn check_counts_lines_past_malformed_ones() {
+    let path = robots_file(
+        "check_malformed",
+        "user-agent: *\r\nbogus line\r\ncrawl-delay: soon\r\ndisallow: /x\r\n",
+    );
+    let robots = path.to_str().unwrap();
+
+    let output = run(&["robots", "check", "--robots", robots, "/x"]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout_lines(&output),
+        vec!["/x: disallowed by \"Disallow: /x\" (line 4)"]
+    );
+}
+
+// The file at the given URL applies, not the site's /robots.txt
+#[test]
+fn check_fetches_the_given_robots_url() {
+    let base = serve(|path| match path {
+        "/staging/robots.txt" => response("200 OK", "user-agent: *\ndisallow: /private\n"),
+        "/robots.txt" => response("200 OK", "user-agent: *\ndisallow: /\n"),
+        _ => response("404 Not Found", ""),
+    });
+    let robots = format!("{}/staging/robots.txt", base);
+
+    let output = run(&[
+        "robots",
+        "check",
+        "--robots",
+        &robots,
+        "/private/x",
+        "/public",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout_lines(&output),
+        vec![
+            "/private/x: disallowed by \"Disallow: /private\" (line 2)",
+            "/public: allowed (no rule matches)",
+        ]
+    );
+}
+
+// 4xx means no robots.txt, 5xx that nothing may be crawled
+#[test]
+fn check_takes_the_status_from_the_robots_url() {
+    let base = serve(|path| match path {
+        "/missing.txt" => response("404 Not Found", ""),
+        _ => response("503 Service Unavailable", ""),
+    });
+
+    for (file, expected) in [
+        ("missing.txt", "/x: allowed (no robots.txt)"),
+        (
+            "failing.txt",
+            "/x: disallowed (robots.txt could not be fetched)",
+        ),
+    ] {
+        let robots = format!("{}/{}", base, file);
+        let output = run(&["robots", "check", "--robots", &robots, "/x"]);
+
+        assert!(output.status.success());
+        assert_eq!(stdout_lines(&output), vec![expected]);
+    }
+}
+
+#[test]
+fn lint_reports_each_problem_with_its_line() {
+    let path = robots_file(
+        "lint",
+        "disallow: /early\nuser-agent: *\n\ndisallow private\ncrawl-delay: -2\n",
+    );
+    let robots = path.to_str().unwrap();
+
+    let output = run(&["robots", "lint", robots]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout_lines(&output),
+        vec![
+            format!(
+                "{}: line 1: disallow before any user-agent line is ignored",
+                robots
+            ),
+            format!(
+                "{}: line 4: no `:` after the field name in \"disallow private\"",
+                robots
+            ),
+            format!("{}: line 5: invalid crawl-delay \"-2\"", robots),
+        ]
+    );
+}
+
+#[test]
+fn lint_passes_a_clean_file() {
+    let path = robots_file("lint_clean", "user-agent: *\ndisallow: /private\n");
+    let robots = path.to_str().unwrap();
+
+    let output = run(&["robots", "lint", robots]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout_lines(&output),
+        vec![format!("{}: no problems found", robots)]
+    );
+}
@@ -0,0 +1,315 @@
+// tests/robots_conformance.rs
+//! Conformance cases for the robots.txt parser and matcher, following the
+//! examples and requirements of RFC 9309.
+use rust_crawler::RobotsTxt;
+
+const UA: &str = "FooBot/1.2 (+https://example.com/bot)";
+
+fn allowed(robots: &str, path: &str) -> bool {
+    RobotsTxt::parse(robots).is_allowed(path, UA)
+}
+
+// 2.1: consecutive user-agent lines share the rules that follow them
+#[test]
+fn consecutive_user_agents_form_one_group() {
+    let robots = "user-agent: a\nuser-agent: foobot\ndisallow: /x\n";
+    assert!(!allowed(robots, "/x"));
+    assert!(!RobotsTxt::parse(robots).is_allowed("/x", "a"));
+    assert_eq!(RobotsTxt::parse(robots).groups.len(), 1);
+}
+
+// 2.1: a user-agent line after a rule starts a new group
+#[test]
+fn user_agent_after_rule_starts_new_group() {
+    let robots = "user-agent: a\ndisallow: /a\nuser-agent: foobot\ndisallow: /b\n";
+    assert!(allowed(robots, "/a"));
+    assert!(!allowed(robots, "/b"));
+    assert_eq!(RobotsTxt::parse(robots).groups.len(), 2);
+}
+
+#[test]
+fn rules_before_any_user_agent_are_ignored() {
+    let robots = "disallow: /\nuser-agent: foobot\nallow: /\n";
+    assert!(allowed(robots, "/page"));
+    assert!(allowed("disallow: /\n", "/page"));
+}
+
+// 2.2.1: product tokens match case-insensitively, ignoring the version
+#[test]
+fn product_token_matches_case_insensitively() {
+    let robots = "User-Agent: FOOBOT\nDisallow: /\n";
+    assert!(!allowed(robots, "/"));
+    assert!(RobotsTxt::parse(robots).is_allowed("/", "FooBotExtra/1.0"));
+}
+
+// 2.2.1: the `*` group applies only when no group names the crawler
+#[test]
+fn star_group_is_a_fallback() {
+    let robots = "user-agent: *\ndisallow: /\n\nuser-agent: foobot\ndisallow: /private\n";
+    assert!(allowed(robots, "/public"));
+    assert!(!allowed(robots, "/private"));
+    assert!(!RobotsTxt::parse(robots).is_allowed("/public", "OtherBot"));
+}
+
+// 2.2.1: groups naming the same crawler are combined
+#[test]
+fn matching_groups_are_merged() {
+    let robots = "user-agent: foobot\ndisallow: /a\n\n\
+                  user-agent: other\ndisallow: /b\n\n\
+                  user-agent: foobot\ndisallow: /c\n";
+    assert!(!allowed(robots, "/a"));
+    assert!(allowed(robots, "/b"));
+    assert!(!allowed(robots, "/c"));
+}
+
+#[test]
+fn no_matching_group_allows_everything() {
+    let robots = "user-agent: other\ndisallow: /\n";
+    assert!(allowed(robots, "/anything"));
+    assert!(allowed("", "/anything"));
+}
+
+// 2.2.2: the most specific (longest) match wins
+#[test]
+fn longest_match_wins() {
+    let robots = "user-agent: *\ndisallow: /folder\nallow: /folder/page\n";
+    assert!(!allowed(robots, "/folder/other"));
+    assert!(allowed(robots, "/folder/page"));
+
+    let robots = "user-agent: *\nallow: /folder\ndisallow: /folder/page\n";
+    assert!(allowed(robots, "/folder/other"));
+    assert!(!allowed(robots, "/folder/page"));
+}
+
+// 2.2.2: between equivalent allow and disallow rules, allow wins
+#[test]
+fn allow_wins_a_tie() {
+    let robots = "user-agent: *\ndisallow: /page\nallow: /page\n";
+    assert!(allowed(robots, "/page"));
+
+    let robots = "user-agent: *\nallow: /page\ndisallow: /page\n";
+    assert!(allowed(robots, "/page"));
+}
+
+// 2.2.2: an empty disallow matches nothing
+#[test]
+fn empty_disallow_allows_everything() {
+    let robots = "user-agent: *\ndisallow:\n";
+    assert!(allowed(robots, "/"));
+    assert!(allowed(robots, "/any/path"));
+}
+
+#[test]
+fn rules_match_path_prefixes() {
+    let robots = "user-agent: *\ndisallow: /fish\n";
+    assert!(!allowed(robots, "/fish"));
+    assert!(!allowed(robots, "/fish.html"));
+    assert!(!allowed(robots, "/fish/salmon.html"));
+    assert!(!allowed(robots, "/fishheads"));
+    assert!(allowed(robots, "/Fish.asp"));
+    assert!(allowed(robots, "/catfish"));
+}
+
+// 2.2.3: `*` matches any sequence of characters
+#[test]
+fn star_wildcard() {
+    let robots = "user-agent: *\ndisallow: /*.php\ndisallow: /fish*\n";
+    assert!(!allowed(robots, "/index.php"));
+    assert!(!allowed(robots, "/folder/filename.php?parameters"));
+    assert!(!allowed(robots, "/fish.html"));
+    assert!(allowed(robots, "/"));
+    assert!(allowed(robots, "/windows.PHP"));
+}
+
+// 2.2.3: a trailing `$` anchors the pattern to the end of the path
+#[test]
+fn dollar_anchor() {
+    let robots = "user-agent: *\ndisallow: /*.php$\ndisallow: /exact$\n";
+    assert!(!allowed(robots, "/filename.php"));
+    assert!(!allowed(robots, "/folder/filename.php"));
+    assert!(allowed(robots, "/filename.php?parameters"));
+    assert!(allowed(robots, "/filename.php5"));
+    assert!(!allowed(robots, "/exact"));
+    assert!(allowed(robots, "/exact/more"));
+}
+
+#[test]
+fn dollar_inside_pattern_is_literal() {
+    let robots = "user-agent: *\ndisallow: /a$b\n";
+    assert!(!allowed(robots, "/a$b"));
+    assert!(allowed(robots, "/a"));
+}
+
+// 2.2.2: rules match the path together with its query
+#[test]
+fn query_is_part_of_the_match() {
+    let robots = "user-agent: *\ndisallow: /search?q=\nallow: /*?lang=\n";
+    assert!(!allowed(robots, "/search?q=rust"));
+    assert!(allowed(robots, "/search"));
+    assert!(allowed(robots, "/page?lang=en"));
+}
+
+// 2.2.2: octets are compared in one percent-encoding
+#[test]
+fn percent_encoding_is_normalized() {
+    let robots = "user-agent: *\ndisallow: /%7Ejoe/\ndisallow: /a%2fb\n";
+    assert!(!allowed(robots, "/~joe/index.html"));
+    assert!(!allowed(robots, "/%7ejoe/index.html"));
+    assert!(!allowed(robots, "/a%2Fb"));
+    // An encoded slash is not the same as a path separator
+    assert!(allowed(robots, "/a/b"));
+
+    let robots = "user-agent: *\ndisallow: /~bob\n";
+    assert!(!allowed(robots, "/%7Ebob"));
+}
+
+#[test]
+fn non_ascii_patterns_match_their_encoding() {
+    let robots = "user-agent: *\ndisallow: /foo/bar/ツ\n";
+    assert!(!allowed(robots, "/foo/bar/%E3%83%84"));
+    assert!(!allowed(robots, "/foo/bar/ツ"));
+
+    let robots = "user-agent: *\ndisallow: /foo/bar/%E3%83%84\n";
+    assert!(!allowed(robots, "/foo/bar/ツ"));
+}
+
+// 2.3.1.3 and 2.3.1.4: robots.txt itself is always allowed
+#[test]
+fn robots_txt_is_always_allowed() {
+    let robots = "user-agent: *\ndisallow: /\n";
+    assert!(allowed(robots, "/robots.txt"));
+    assert!(!allowed(robots, "/robots.txt.bak"));
+}
+
+// 2.2: field names are case-insensitive and may have spaces around the colon
+#[test]
+fn keys_are_case_insensitive() {
+    let robots = "USER-AGENT : *\nDisAllow :  /private \n";
+    assert!(!allowed(robots, "/private"));
+    assert!(allowed(robots, "/public"));
+}
+
+#[test]
+fn comments_are_ignored() {
+    let robots = "# comment line\nuser-agent: * # everyone\n\
+                  disallow: /private # keep out\n#disallow: /public\n";
+    assert!(!allowed(robots, "/private"));
+    assert!(allowed(robots, "/public"));
+}
+
+// 2.2: lines end with CR, LF or CRLF
+#[test]
+fn line_endings() {
+    for robots in [
+        "user-agent: *\r\ndisallow: /a\r\n",
+        "user-agent: *\rdisallow: /a\r",
+        "user-agent: *\ndisallow: /a",
+    ] {
+        assert!(!allowed(robots, "/a"), "{:?}", robots);
+        assert!(allowed(robots, "/b"), "{:?}", robots);
+    }
+}
+
+#[test]
+fn byte_order_mark_is_skipped() {
+    let robots = "\u{feff}user-agent: *\ndisallow: /a\n";
+    assert!(!allowed(robots, "/a"));
+}
+
+#[test]
+fn unknown_and_malformed_lines_are_skipped() {
+    let robots = "user-agent: *\nfoo: bar\nthis line has no colon\ndisallow: /a\n";
+    assert!(!allowed(robots, "/a"));
+    assert_eq!(RobotsTxt::parse(robots).groups.len(), 1);
+}
+
+// 2.5: crawlers must parse at least 500 KiB
+#[test]
+fn large_files_are_parsed_up_to_the_limit() {
+    let mut robots = String::from("user-agent: *\ndisallow: /a\n");
+    while robots.len() < 400 * 1024 {
+        robots.push_str("# padding padding padding padding padding padding\n");
+    }
+    robots.push_str("disallow: /b\n");
+    assert!(!allowed(&robots, "/b"));
+
+    while robots.len() < 600 * 1024 {
+        robots.push_str("# padding padding padding padding padding padding\n");
+    }
+    robots.push_str("disallow: /c\n");
+    assert!(allowed(&robots, "/c"));
+}
+
+#[test]
+fn sitemaps_are_global() {
+    let robots = "sitemap: https://example.com/a.xml\nuser-agent: other\n\
+                  disallow: /\nSitemap: https://example.com/b.xml\n";
+    let parsed = RobotsTxt::parse(robots);
+    assert_eq!(
+        parsed.sitemaps,
+        vec!["https://example.com/a.xml", "https://example.com/b.xml"]
+    );
+    // A sitemap line doesn't end the group it appears in
+    assert!(!parsed.is_allowed("/", "other"));
+}
+
+#[test]
+fn crawl_delay_follows_the_group() {
+    let robots = "user-agent: *\ncrawl-delay: 10\n\nuser-agent: foobot\ncrawl-delay: 0.5\n";
+    let parsed = RobotsTxt::parse(robots);
+    assert_eq!(parsed.crawl_delay(UA), Some(0.5));
+    assert_eq!(parsed.crawl_delay("OtherBot"), Some(10.0));
+    assert_eq!(RobotsTxt::parse("user-agent: *\n").crawl_delay(UA), None);
+}
+
+#[test]
+fn verdict_reports_the_deciding_rule() {
+    let robots = "user-agent: *\nallow: /a\n\ndisallow: /a/b\n";
+    let parsed = RobotsTxt::parse(robots);
+
+    let verdict = parsed.check("/a/b/c", UA);
+    assert!(!verdict.allowed);
+    let rule = verdict.rule.expect("a rule matched");
+    assert_eq!(rule.to_string(), "Disallow: /a/b");
+    assert_eq!(rule.line, 4);
+
+    let verdict = parsed.check("/z", UA);
+    assert!(verdict.allowed);
+    assert!(verdict.rule.is_none());
+}
+
+// Request-rate isn't in RFC 9309 either; it reads as requests per period
+#[test]
+fn request_rate_sets_an_interval() {
+    let robots =
+        "user-agent: *\nrequest-rate: 1/5\n\nuser-agent: foobot\nrequest-rate: 3/1m 0800-1700\n";
+    let parsed = RobotsTxt::parse(robots);
+    assert_eq!(
+        parsed.request_rate("OtherBot").map(|r| r.interval()),
+        Some(5.0)
+    );
+    assert_eq!(parsed.request_rate(UA).map(|r| r.interval()), Some(20.0));
+}
+
+#[test]
+fn malformed_request_rate_is_ignored() {
+    for value in ["", "5", "0/5s", "1/", "1/5x", "a/b"] {
+        let robots = format!("user-agent: *\nrequest-rate: {}\n", value);
+        assert_eq!(
+            RobotsTxt::parse(&robots).request_rate(UA),
+            None,
+            "{:?}",
+            value
+        );
+    }
+}
+
+#[test]
+fn longest_of_crawl_delay_and_request_rate_applies() {
+    let robots = "user-agent: *\ncrawl-delay: 2\nrequest-rate: 1/10s\n";
+    assert_eq!(RobotsTxt::parse(robots).min_interval(UA), Some(10.0));
+
+    let robots = "user-agent: *\ncrawl-delay: 2\nrequest-rate: 1/1s\n";
+    assert_eq!(RobotsTxt::parse(robots).min_interval(UA), Some(2.0));
+    assert_eq!(RobotsTxt::parse("user-agent: *\n").min_interval(UA), None);
+}
@@ -0,0 +1,253 @@
+// tests/robots_fetch.rs
+//! How robots.txt responses are handled (RFC 9309 2.3.1) and how long they
+//! are cached, against a local server.
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::redirect::Policy;
+use reqwest::Client;
+use rust_crawler::{RobotsChecker, RobotsConfig, RobotsStatus};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const UA: &str = "FooBot";
+
+// Serve `respond(path, request number)` for every request, returning the
+// site's base URL and a count of requests
+async fn serve<F>(respond: F) -> (String, Arc<AtomicUsize>)
+where
+    F: Fn(&str, usize) -> String + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let hits = Arc::new(AtomicUsize::new(0));
+    let respond = Arc::new(respond);
+
+    let counter = Arc::clone(&hits);
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                continue;
+            };
+            let respond = Arc::clone(&respond);
+            let counter = Arc::clone(&counter);
+
+            tokio::spawn(async move {
+                let mut buf = vec![0; 4096];
+                let n = match socket.read(&mut buf).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => n,
+                };
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                let hit = counter.fetch_add(1, Ordering::SeqCst);
+                let _ = socket.write_all(respond(path, hit).as_bytes()).await;
+            });
+        }
+    });
+
+    (base, hits)
+}
+
+fn response(status: &str, headers: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        headers,
+        body.len(),
+        body
+    )
+}
+
+fn checker(min_ttl: u64, max_ttl: u64) -> RobotsChecker {
+    let config = RobotsConfig {
+        min_ttl: Duration::from_secs(min_ttl),
+        max_ttl: Duration::from_secs(max_ttl),
+        ..RobotsConfig::default()
+    };
+    let client = Client::builder()
+        .redirect(Policy::limited(config.max_redirects))
+        .build()
+        .unwrap();
+    RobotsChecker::with_config(client, config).unwrap()
+}
+
+async fn allowed(checker: &RobotsChecker, url: &str) -> bool {
+    checker.is_allowed(url, UA).await.unwrap()
+}
+
+const PRIVATE: &str = "user-agent: *\ndisallow: /private\n";
+
+#[tokio::test]
+async fn success_applies_the_rules() {
+    let (base, _) = serve(|_, _| response("200 OK", "", PRIVATE)).await;
+    let checker = checker(60, 3600);
+
+    assert!(allowed(&checker, &format!("{}/public", base)).await);
+    assert!(!allowed(&checker, &format!("{}/private", base)).await);
+    assert_eq!(checker.status(&base).await, Some(RobotsStatus::Fetched));
+}
+
+// 2.3.1.3: a 4xx other than 429 means there is no robots.txt
+#[tokio::test]
+async fn client_errors_allow_everything() {
+    for status in ["404 Not Found", "403 Forbidden", "410 Gone"] {
+        let (base, _) = serve(move |_, _| response(status, "", PRIVATE)).await;
+        let checker = checker(60, 3600);
+
+        assert!(
+            allowed(&checker, &format!("{}/private", base)).await,
+            "{}",
+            status
+        );
+        assert_eq!(
+            checker.status(&base).await,
+            Some(RobotsStatus::Unavailable),
+            "{}",
+            status
+        );
+    }
+}
+
+// 2.3.1.4: a 5xx means the site can't be crawled; 429 is treated the same
+#[tokio::test]
+async fn server_errors_and_429_disallow_everything() {
+    for status in [
+        "500 Internal Server Error",
+        "503 Service Unavailable",
+        "429 Too Many Requests",
+    ] {
+        let (base, _) = serve(move |_, _| response(status, "", "")).await;
+        let checker = checker(60, 3600);
+
+        assert!(
+            !allowed(&checker, &format!("{}/public", base)).await,
+            "{}",
+            status
+        );
+        assert_eq!(
+            checker.status(&base).await,
+            Some(RobotsStatus::Unreachable),
+            "{}",
+            status
+        );
+    }
+}
+
+// 2.3.1.4: a site that can't be connected to is disallowed
+#[tokio::test]
+async fn unreachable_site_disallows_everything() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+    let checker = checker(60, 3600);
+
+    assert!(!allowed(&checker, &format!("{}/public", base)).await);
+    assert_eq!(checker.status(&base).await, Some(RobotsStatus::Unreachable));
+}
+
+// 2.3.1.2: redirects are followed, and the rules found at the end apply
+#[tokio::test]
+async fn redirects_are_followed() {
+    let (base, _) = serve(|path, _| match path {
+        "/robots.txt" => response("301 Moved Permanently", "Location: /elsewhere\r\n", ""),
+        _ => response("200 OK", "", PRIVATE),
+    })
+    .await;
+    let checker = checker(60, 3600);
+
+    assert!(allowed(&checker, &format!("{}/public", base)).await);
+    assert!(!allowed(&checker, &format!("{}/private", base)).await);
+    assert_eq!(checker.status(&base).await, Some(RobotsStatus::Fetched));
+}
+
+// 2.3.1.2: past the redirect limit the file counts as unavailable
+#[tokio::test]
+async fn too_many_redirects_allow_everything() {
+    let (base, hits) = serve(|path, _| {
+        let next = format!("Location: {}x\r\n", path);
+        response("302 Found", &next, "")
+    })
+    .await;
+    let checker = checker(60, 3600);
+
+    assert!(allowed(&checker, &format!("{}/private", base)).await);
+    assert_eq!(checker.status(&base).await, Some(RobotsStatus::Unavailable));
+    // The chain is given up on, not followed forever
+    assert!(hits.load(Ordering::SeqCst) <= RobotsConfig::default().max_redirects + 1);
+}
+
+// 2.4: the last copy is kept while the file can't be fetched
+#[tokio::test]
+async fn expired_copy_is_used_while_unreachable() {
+    let (base, hits) = serve(|_, hit| match hit {
+        0 => response("200 OK", "", PRIVATE),
+        _ => response("503 Service Unavailable", "", ""),
+    })
+    .await;
+    // Nothing stays fresh, so every check fetches again
+    let checker = checker(0, 0);
+
+    assert!(allowed(&checker, &format!("{}/public", base)).await);
+    assert!(allowed(&checker, &format!("{}/public", base)).await);
+    assert!(!allowed(&checker, &format!("{}/private", base)).await);
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+}
+
+// Fetch a site's robots.txt twice with the given TTL bounds, and count how
+// often the server was asked
+async fn fetches(cache_control: &'static str, min_ttl: u64, max_ttl: u64) -> usize {
+    let (base, hits) = serve(move |_, _| {
+        let headers = format!("Cache-Control: {}\r\n", cache_control);
+        response("200 OK", &headers, PRIVATE)
+    })
+    .await;
+    let checker = checker(min_ttl, max_ttl);
+
+    allowed(&checker, &format!("{}/a", base)).await;
+    allowed(&checker, &format!("{}/b", base)).await;
+    hits.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn max_age_sets_the_ttl() {
+    assert_eq!(fetches("max-age=600", 0, 3600).await, 1);
+    assert_eq!(fetches("public, MAX-AGE=\"600\"", 0, 3600).await, 1);
+    assert_eq!(fetches("max-age=0", 0, 3600).await, 2);
+    assert_eq!(fetches("no-cache", 0, 3600).await, 2);
+    assert_eq!(fetches("no-store, max-age=600", 0, 3600).await, 2);
+}
+
+#[tokio::test]
+async fn ttl_is_clamped_to_the_configured_bounds() {
+    // Below min_ttl is raised to it
+    assert_eq!(fetches("max-age=0", 60, 3600).await, 1);
+    assert_eq!(fetches("no-store", 60, 3600).await, 1);
+    // Above max_ttl is lowered to it
+    assert_eq!(fetches("max-age=86400", 0, 0).await, 2);
+    // Without a max-age, max_ttl applies
+    assert_eq!(fetches("public", 0, 0).await, 2);
+    assert_eq!(fetches("public", 0, 3600).await, 1);
+    // A max_ttl below min_ttl doesn't undercut it
+    assert_eq!(fetches("max-age=0", 60, 0).await, 1);
+}
+
+#[tokio::test]
+async fn persistent_cache_outlives_the_checker() {
+    let path = std::env::temp_dir().join(format!("robots_fetch_{}.redb", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let (base, hits) = serve(|_, _| response("200 OK", "", PRIVATE)).await;
+
+    let config = RobotsConfig {
+        cache_path: Some(path.to_string_lossy().into_owned()),
+        ..RobotsConfig::default()
+    };
+    for _ in 0..2 {
+        let checker = RobotsChecker::with_config(Client::new(), config.clone()).unwrap();
+        assert!(!allowed(&checker, &format!("{}/private", base)).await);
+    }
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    let _ = std::fs::remove_file(&path);
+}
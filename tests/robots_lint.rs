@@ -0,0 +1,118 @@
+// tests/robots_lint.rs
+//! Diagnostics from `robots_txt::lint` on malformed files, and the line
+//! numbers they point at.
+use rust_crawler::robots_txt::{lint, LintIssue};
+
+fn issues(robots: &str) -> Vec<(usize, String)> {
+    lint(robots)
+        .into_iter()
+        .map(|issue| (issue.line, issue.message))
+        .collect()
+}
+
+#[test]
+fn well_formed_file_has_no_issues() {
+    let robots = "# comment\nuser-agent: *\ndisallow: /private\nallow: /private/ok$\n\n\
+                  crawl-delay: 1.5\nrequest-rate: 1/5s\nsitemap: https://example.com/s.xml\n";
+    assert_eq!(lint(robots), Vec::<LintIssue>::new());
+}
+
+#[test]
+fn each_problem_is_reported_on_its_line() {
+    let robots = "# comment\n\
+                  sitemap: /sitemap.xml\n\
+                  disallow: /early\n\
+                  user-agent: FooBot/1.0\n\
+                  user-agent:\n\
+                  disallow /missing-colon\n\
+                  allow: page\n\
+                  disallow: /a$b\n\
+                  crawl-delay: soon\n\
+                  request-rate: fast\n\
+                  noindex: /x\n\
+                  \n\
+                  disallow: /fine\n";
+
+    assert_eq!(
+        issues(robots),
+        vec![
+            (
+                2,
+                "sitemap \"/sitemap.xml\" is not an absolute URL".to_string()
+            ),
+            (
+                3,
+                "disallow before any user-agent line is ignored".to_string()
+            ),
+            (
+                4,
+                "user-agent \"FooBot/1.0\" is matched by its product token `foobot` only"
+                    .to_string()
+            ),
+            (5, "user-agent has no product token".to_string()),
+            (
+                6,
+                "no `:` after the field name in \"disallow /missing-colon\"".to_string()
+            ),
+            (7, "pattern \"page\" should start with `/`".to_string()),
+            (
+                8,
+                "`$` in \"/a$b\" is only an anchor at the end of a pattern".to_string()
+            ),
+            (9, "invalid crawl-delay \"soon\"".to_string()),
+            (10, "invalid request-rate \"fast\"".to_string()),
+            (11, "unknown field `noindex`".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn issue_displays_its_line() {
+    let issue = &lint("user-agent: *\ncrawl-delay: -1\n")[0];
+    assert_eq!(issue.to_string(), "line 2: invalid crawl-delay \"-1\"");
+}
+
+// Line numbers count every kind of line ending, as the parser does
+#[test]
+fn line_numbers_follow_any_line_ending() {
+    for robots in [
+        "user-agent: *\r\n\r\nbogus\r\n",
+        "user-agent: *\r\rbogus\r",
+        "\u{feff}user-agent: *\n\nbogus\n",
+    ] {
+        assert_eq!(
+            issues(robots),
+            vec![(3, "no `:` after the field name in \"bogus\"".to_string())],
+            "{:?}",
+            robots
+        );
+    }
+}
+
+#[test]
+fn comments_dont_hide_problems() {
+    assert_eq!(
+        issues("user-agent: * # everyone\ncrawl-delay: x # slow down\n"),
+        vec![(2, "invalid crawl-delay \"x\"".to_string())]
+    );
+    // A line that is only a comment is fine
+    assert_eq!(issues("# disallow /\nuser-agent: *\n"), vec![]);
+}
+
+#[test]
+fn oversized_file_is_reported_at_the_last_line_read() {
+    let line = "disallow: /x\n";
+    let robots = format!("user-agent: *\n{}", line.repeat(50_000));
+    let lines_read = 1 + (500 * 1024 - "user-agent: *\n".len()).div_ceil(line.len());
+
+    let issues = issues(&robots);
+    let (last_line, message) = issues.last().expect("size is reported");
+    assert_eq!(*last_line, lines_read);
+    assert_eq!(
+        message,
+        &format!(
+            "file is {} bytes; only the first 512000 are read",
+            robots.len()
+        )
+    );
+}